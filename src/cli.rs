@@ -0,0 +1,184 @@
+//! Folder overrides from the command line and portable mode, resolved once at startup before
+//! anything reads the BeamMM directory.
+
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Next to the executable, this file puts the BeamMM directory (and so the presets) beside
+/// the binary instead of in the user's local app data.
+const PORTABLE_MARKER: &str = "portable.txt";
+
+const USAGE: &str = "Usage: beammm-gui [--userfolder <dir>] [--mods-dir <dir>] [--beammm-dir <dir>]
+
+  --userfolder <dir>   BeamNG.drive userfolder, holding one folder per game version
+  --mods-dir <dir>     Mods folder, instead of the one of the game's current version
+  --beammm-dir <dir>   Folder for BeamMM's settings and presets";
+
+#[derive(Debug, Default)]
+pub struct PathOverrides {
+    pub userfolder: Option<PathBuf>,
+    pub mods_dir: Option<PathBuf>,
+    beammm_dir: Option<PathBuf>,
+    /// Set when the BeamMM directory comes from the portable marker.
+    pub portable: bool,
+    /// Usage text, and what was wrong with the arguments, when they can't be used. Shown
+    /// instead of loading anything.
+    pub problem: Option<String>,
+}
+
+static OVERRIDES: OnceLock<PathOverrides> = OnceLock::new();
+
+/// The overrides in effect, see `init`.
+pub fn overrides() -> &'static PathOverrides {
+    OVERRIDES.get_or_init(PathOverrides::default)
+}
+
+/// Parse the command line and look for the portable marker. On `--help` or bad arguments,
+/// prints usage and exits when run from a terminal. Otherwise (e.g. a Windows shortcut,
+/// with no console to print to) the usage is kept in `problem` for the window to show.
+pub fn init() {
+    let mut overrides = match PathOverrides::parse(std::env::args().skip(1)) {
+        Ok(Some(overrides)) => overrides,
+        Ok(None) => {
+            if std::io::stdout().is_terminal() {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            PathOverrides {
+                problem: Some(USAGE.to_owned()),
+                ..PathOverrides::default()
+            }
+        }
+        Err(e) => {
+            let problem = format!("{}\n\n{}", e, USAGE);
+            if std::io::stderr().is_terminal() {
+                eprintln!("{}", problem);
+                std::process::exit(2);
+            }
+            PathOverrides {
+                problem: Some(problem),
+                ..PathOverrides::default()
+            }
+        }
+    };
+    if overrides.beammm_dir.is_none() {
+        if let Some(dir) = portable_dir() {
+            overrides.beammm_dir = Some(dir);
+            overrides.portable = true;
+        }
+    }
+    // Only fails if something already read the defaults, which would be a bug
+    if OVERRIDES.set(overrides).is_err() {
+        eprintln!("Path overrides were read before they were set");
+    }
+}
+
+impl PathOverrides {
+    /// Parse `--flag <dir>` and `--flag=<dir>` arguments. `None` means help was asked for.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut overrides = Self::default();
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Ok(None);
+            }
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg, None),
+            };
+            let slot = match flag.as_str() {
+                "--userfolder" => &mut overrides.userfolder,
+                "--mods-dir" => &mut overrides.mods_dir,
+                "--beammm-dir" => &mut overrides.beammm_dir,
+                _ => return Err(format!("Unknown argument {}", flag)),
+            };
+            let value = inline_value
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a folder", flag))?;
+            *slot = Some(PathBuf::from(value));
+        }
+        Ok(Some(overrides))
+    }
+
+    /// The BeamMM directory: from `--beammm-dir`, portable mode or beammm's default, in that
+    /// order. Created if it doesn't exist.
+    pub fn beammm_dir(&self) -> beammm::Result<PathBuf> {
+        match &self.beammm_dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                Ok(dir.clone())
+            }
+            None => beammm::path::beammm_dir(),
+        }
+    }
+}
+
+/// `BeamMM` beside the executable, if the portable marker is there.
+fn portable_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let exe_dir = exe.parent()?;
+    exe_dir
+        .join(PORTABLE_MARKER)
+        .is_file()
+        .then(|| exe_dir.join("BeamMM"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<PathOverrides>, String> {
+        PathOverrides::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_override_nothing() {
+        let overrides = parse(&[]).unwrap().unwrap();
+        assert_eq!(overrides.userfolder, None);
+        assert_eq!(overrides.mods_dir, None);
+        assert_eq!(overrides.beammm_dir, None);
+        assert!(!overrides.portable);
+    }
+
+    #[test]
+    fn separate_and_inline_values() {
+        let overrides = parse(&["--userfolder", "/games/beamng", "--mods-dir=/mods", "--beammm-dir", "a=b"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(overrides.userfolder, Some(PathBuf::from("/games/beamng")));
+        assert_eq!(overrides.mods_dir, Some(PathBuf::from("/mods")));
+        // Only the flag is split at '=', the separate value is taken as is
+        assert_eq!(overrides.beammm_dir, Some(PathBuf::from("a=b")));
+    }
+
+    #[test]
+    fn inline_value_keeps_later_equals_signs() {
+        let overrides = parse(&["--mods-dir=C:\\a=b"]).unwrap().unwrap();
+        assert_eq!(overrides.mods_dir, Some(PathBuf::from("C:\\a=b")));
+    }
+
+    #[test]
+    fn later_flag_wins() {
+        let overrides = parse(&["--mods-dir", "/one", "--mods-dir=/two"]).unwrap().unwrap();
+        assert_eq!(overrides.mods_dir, Some(PathBuf::from("/two")));
+    }
+
+    #[test]
+    fn missing_value_is_an_error() {
+        assert_eq!(parse(&["--userfolder"]).unwrap_err(), "--userfolder needs a folder");
+    }
+
+    #[test]
+    fn unknown_flag_is_an_error() {
+        assert_eq!(parse(&["--verbose"]).unwrap_err(), "Unknown argument --verbose");
+        assert_eq!(parse(&["--nope=1"]).unwrap_err(), "Unknown argument --nope");
+        assert!(parse(&["somefolder"]).is_err());
+    }
+
+    #[test]
+    fn help_wins_wherever_it_is() {
+        assert!(parse(&["-h"]).unwrap().is_none());
+        assert!(parse(&["--help"]).unwrap().is_none());
+        assert!(parse(&["--mods-dir", "/mods", "-h"]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! Which columns the mods table shows, and in what order.
+
+use crate::SortOption;
+use egui_extras::Column;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModColumn {
+    Select,
+    Active,
+    Name,
+    Filename,
+    DateAdded,
+    Size,
+    Type,
+    Fullpath,
+    Presets,
+    Version,
+    Author,
+}
+
+impl ModColumn {
+    pub const ALL: [ModColumn; 11] = [
+        ModColumn::Select,
+        ModColumn::Active,
+        ModColumn::Name,
+        ModColumn::Filename,
+        ModColumn::DateAdded,
+        ModColumn::Size,
+        ModColumn::Type,
+        ModColumn::Fullpath,
+        ModColumn::Presets,
+        ModColumn::Version,
+        ModColumn::Author,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ModColumn::Select => "Select",
+            ModColumn::Active => "Active",
+            ModColumn::Name => "Mod Name",
+            ModColumn::Filename => "Filename",
+            ModColumn::DateAdded => "Date Added",
+            ModColumn::Size => "Size",
+            ModColumn::Type => "Type",
+            ModColumn::Fullpath => "Fullpath",
+            ModColumn::Presets => "Presets",
+            ModColumn::Version => "Version",
+            ModColumn::Author => "Author",
+        }
+    }
+
+    /// What clicking this column's header sorts by, if anything.
+    pub fn sort_option(self) -> Option<SortOption> {
+        match self {
+            ModColumn::Select => Some(SortOption::Selection),
+            ModColumn::Active => Some(SortOption::Status),
+            ModColumn::Name => Some(SortOption::Name),
+            ModColumn::Filename => Some(SortOption::Filename),
+            ModColumn::DateAdded => Some(SortOption::Date),
+            ModColumn::Size => Some(SortOption::Size),
+            ModColumn::Type => Some(SortOption::ModType),
+            ModColumn::Fullpath => Some(SortOption::Fullpath),
+            ModColumn::Presets => None,
+            ModColumn::Version => Some(SortOption::Version),
+            ModColumn::Author => Some(SortOption::Author),
+        }
+    }
+
+    /// The mod name column is always shown; it's what identifies a row.
+    pub fn can_hide(self) -> bool {
+        self != ModColumn::Name
+    }
+
+    pub fn table_column(self) -> Column {
+        match self {
+            ModColumn::Select => Column::auto().resizable(false),
+            ModColumn::Active => Column::exact(75.0).resizable(false),
+            ModColumn::Name => Column::remainder().resizable(true),
+            ModColumn::Fullpath => Column::initial(250.0).resizable(true),
+            ModColumn::Filename => Column::initial(150.0).resizable(true),
+            // Chips don't truncate like labels do
+            ModColumn::Presets => Column::initial(150.0).resizable(true).clip(true),
+            ModColumn::DateAdded => Column::initial(85.0).resizable(true),
+            ModColumn::Size | ModColumn::Version => Column::initial(70.0).resizable(true),
+            ModColumn::Type | ModColumn::Author => Column::initial(100.0).resizable(true),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColumnEntry {
+    pub column: ModColumn,
+    pub visible: bool,
+}
+
+/// Every column exactly once, in display order, each shown or hidden.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<ColumnEntry>", into = "Vec<ColumnEntry>")]
+pub struct ColumnLayout {
+    entries: Vec<ColumnEntry>,
+}
+
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        let visible = [
+            ModColumn::Select,
+            ModColumn::Active,
+            ModColumn::Name,
+            ModColumn::Fullpath,
+            ModColumn::Type,
+        ];
+        Self::from(
+            ModColumn::ALL
+                .iter()
+                .map(|&column| ColumnEntry {
+                    column,
+                    visible: visible.contains(&column),
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl From<Vec<ColumnEntry>> for ColumnLayout {
+    /// Drops duplicates and appends any columns missing from `entries` (e.g. ones added in a
+    /// newer version) as hidden.
+    fn from(entries: Vec<ColumnEntry>) -> Self {
+        let mut normalized: Vec<ColumnEntry> = Vec::with_capacity(ModColumn::ALL.len());
+        for entry in entries {
+            if !normalized.iter().any(|e| e.column == entry.column) {
+                normalized.push(ColumnEntry {
+                    visible: entry.visible || !entry.column.can_hide(),
+                    ..entry
+                });
+            }
+        }
+        for column in ModColumn::ALL {
+            if !normalized.iter().any(|e| e.column == column) {
+                normalized.push(ColumnEntry {
+                    column,
+                    visible: !column.can_hide(),
+                });
+            }
+        }
+        Self {
+            entries: normalized,
+        }
+    }
+}
+
+impl From<ColumnLayout> for Vec<ColumnEntry> {
+    fn from(layout: ColumnLayout) -> Self {
+        layout.entries
+    }
+}
+
+impl ColumnLayout {
+    pub fn entries(&self) -> &[ColumnEntry] {
+        &self.entries
+    }
+
+    /// Visible columns in display order.
+    pub fn visible(&self) -> Vec<ModColumn> {
+        self.entries
+            .iter()
+            .filter(|e| e.visible)
+            .map(|e| e.column)
+            .collect()
+    }
+
+    pub fn is_visible(&self, column: ModColumn) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.column == column && e.visible)
+    }
+
+    pub fn set_visible(&mut self, column: ModColumn, visible: bool) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.column == column) {
+            entry.visible = visible || !column.can_hide();
+        }
+    }
+
+    /// Move the entry at `index` one place up (`-1`) or down (`1`).
+    pub fn move_entry(&mut self, index: usize, offset: isize) {
+        let Some(target) = index.checked_add_signed(offset) else {
+            return;
+        };
+        if target < self.entries.len() {
+            self.entries.swap(index, target);
+        }
+    }
+}
+
+/// Human readable file size, e.g. `12.3 MB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
@@ -1,27 +1,535 @@
-use crate::App;
+use crate::columns::{format_size, ColumnLayout, ModColumn};
+use crate::conflicts::conflicts_of;
+use crate::filter::{format_date, PresetFilter};
+use crate::game_versions::TransferMode;
+use crate::gui_config::Theme;
+use crate::loader::Loader;
+use crate::preset_check::find_replacements;
+use crate::preset_meta::{ExclusionGroup, GroupMember};
 use crate::SortOption;
+use crate::{App, Reload, StagedMod};
 use beammm::Preset;
 use eframe::egui;
 use egui::RichText;
 use egui_extras::{Column, TableBuilder};
 
-pub fn title_panel(ctx: &egui::Context, app_data: &App) {
+pub fn title_panel(ctx: &egui::Context, app_data: &mut App) {
     egui::TopBottomPanel::top("title_panel").show(ctx, |ui| {
         ui.horizontal(|ui| {
             ui.heading("BeamMM.gui");
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let (icon, next_theme) = match app_data.theme {
+                    Theme::Dark => ("☀", Theme::Light),
+                    Theme::Light => ("🌙", Theme::Dark),
+                };
+                if ui.button(icon).on_hover_text("Toggle light/dark theme").clicked() {
+                    app_data.theme = next_theme;
+                    ui.ctx().set_visuals(next_theme.visuals());
+                }
+                ui.separator();
                 ui.label(&app_data.version);
                 ui.label("Version: ");
                 ui.separator();
-                ui.label(&app_data.beamng_version);
+                game_version_menu(ui, app_data);
                 ui.label("BeamNG.drive: ");
+                ui.separator();
+                profile_menu(ui, app_data);
+                ui.label("Profile: ");
+                if crate::cli::overrides().portable {
+                    ui.separator();
+                    ui.label("Portable")
+                        .on_hover_text(format!("Settings and presets are in {}", app_data.beam_paths.beammm_dir.display()));
+                }
+                let load_errors: Vec<&str> = [&app_data.gui_config_error, &app_data.preset_meta.load_error]
+                    .into_iter()
+                    .filter_map(|e| e.as_deref())
+                    .collect();
+                if !load_errors.is_empty() {
+                    ui.separator();
+                    ui.label(RichText::new("⚠ Settings not saved").color(egui::Color32::from_rgb(200, 150, 50)))
+                        .on_hover_text(format!(
+                            "{}\nThese files are left as they are until they can be read.",
+                            load_errors.join("\n")
+                        ));
+                }
+                ui.separator();
+                play_controls(ui, app_data);
+            });
+        });
+        if app_data.game_running {
+            game_running_banner(ui, app_data);
+        }
+        if let Some(error) = &app_data.launch_error {
+            ui.label(RichText::new(error).color(egui::Color32::from_rgb(200, 50, 50)));
+        }
+        if let Some(notice) = &app_data.group_conflict_notice {
+            let mut dismissed = false;
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(notice).color(egui::Color32::from_rgb(200, 150, 50)));
+                dismissed = ui.small_button("✖").on_hover_text("Dismiss").clicked();
+            });
+            if dismissed {
+                app_data.group_conflict_notice = None;
+            }
+        }
+    });
+}
+
+/// The Play button and the settings for what it does. Laid out right to left.
+fn play_controls(ui: &mut egui::Ui, app_data: &mut App) {
+    ui.menu_button("⚙", |ui| {
+        let launch = &mut app_data.launch;
+        ui.label("Apply before starting:");
+        ui.radio_value(&mut launch.preset, None, "Mods as they are");
+        for (name, _) in &app_data.presets {
+            ui.radio_value(&mut launch.preset, Some(name.clone()), format!("Only preset {}", name));
+        }
+        ui.separator();
+        ui.label("Launch command:");
+        ui.text_edit_singleline(&mut launch.command).on_hover_text(
+            "A steam:// URL, the game executable, or a full command line such as a Proton invocation. Quote paths with spaces.",
+        );
+        ui.add_enabled(
+            launch.preset.is_some(),
+            egui::Checkbox::new(&mut launch.restore_after_exit, "Restore the previous mods when the game exits"),
+        )
+        .on_hover_text("BeamMM has to stay open until the game exits to restore them");
+    })
+    .response
+    .on_hover_text("Play settings");
+
+    let label = match &app_data.launch.preset {
+        Some(preset) => format!("▶ Play {}", preset),
+        None => "▶ Play".to_owned(),
+    };
+    let waiting = app_data.play_session.is_some();
+    let button = ui
+        .add_enabled(!app_data.game_running && !waiting, egui::Button::new(label))
+        .on_disabled_hover_text(if waiting {
+            "Waiting for the game to exit to restore the previous mods"
+        } else {
+            "The game is already running"
+        });
+    if button.clicked() {
+        app_data.play();
+    }
+}
+
+/// Warns that db.json writes are held back while the game runs, with a way to write anyway.
+fn game_running_banner(ui: &mut egui::Ui, app_data: &mut App) {
+    let color = egui::Color32::from_rgb(200, 150, 50);
+    let signal = app_data.game_signal.clone().unwrap_or_default();
+    ui.horizontal(|ui| {
+        if app_data.write_while_running {
+            ui.label(RichText::new("BeamNG.drive is running. Changes are written anyway until it exits.").color(color));
+            ui.label(RichText::new(format!("({})", signal)).weak());
+            return;
+        }
+        ui.label(
+            RichText::new("BeamNG.drive is running. Changes to active mods are held back until it exits.").color(color),
+        );
+        ui.label(RichText::new(format!("({})", signal)).weak());
+        if app_data.queued_mod_states.is_some() {
+            ui.label(RichText::new("Changes are waiting.").strong());
+        }
+        if ui
+            .button("Write Anyway")
+            .on_hover_text("The game may overwrite these changes or get confused by them")
+            .clicked()
+        {
+            app_data.override_write_lock();
+        }
+    });
+}
+
+/// Switches between profiles and opens the profile manager.
+fn profile_menu(ui: &mut egui::Ui, app_data: &mut App) {
+    let current = app_data.active_profile.clone().unwrap_or_else(|| "Default".to_owned());
+    ui.menu_button(current, |ui| {
+        let names = std::iter::once(None).chain(app_data.profiles.iter().map(|p| Some(p.name.clone())));
+        let mut picked = None;
+        for name in names {
+            let label = name.clone().unwrap_or_else(|| "Default".to_owned());
+            let blocker = app_data.pending_work();
+            if ui
+                .add_enabled(name != app_data.active_profile && blocker.is_none(), egui::Button::new(label))
+                .on_disabled_hover_text(blocker.unwrap_or("This profile is active"))
+                .clicked()
+            {
+                picked = Some(name);
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui.button("Manage Profiles…").clicked() {
+            app_data.profiles_open = true;
+            ui.close_menu();
+        }
+        if let Some(name) = picked {
+            app_data.switch_profile(name);
+        }
+    })
+    .response
+    .on_hover_text(format!("Mods: {}", app_data.beam_paths.mods_dir.display()));
+}
+
+/// Lists profiles and adds new ones.
+pub fn profiles_window(ctx: &egui::Context, app_data: &mut App) {
+    if !app_data.profiles_open {
+        return;
+    }
+    let mut open = true;
+    let mut switch_to = None;
+    let mut delete = None;
+    egui::Window::new("Profiles")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(450.0)
+        .show(ctx, |ui| {
+            ui.label(
+                RichText::new("The Default profile uses the folders found automatically. Switching reloads everything.")
+                    .small(),
+            );
+            let blocker = app_data.pending_work();
+            for profile in &app_data.profiles {
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(&profile.name).strong());
+                    let active = app_data.active_profile.as_ref() == Some(&profile.name);
+                    if ui
+                        .add_enabled(!active && blocker.is_none(), egui::Button::new("Switch"))
+                        .on_disabled_hover_text(blocker.unwrap_or("This profile is active"))
+                        .clicked()
+                    {
+                        switch_to = Some(Some(profile.name.clone()));
+                    }
+                    // Deleting the active profile switches back to the default one
+                    if ui
+                        .add_enabled(!active || blocker.is_none(), egui::Button::new("Delete"))
+                        .on_disabled_hover_text(blocker.unwrap_or_default())
+                        .clicked()
+                    {
+                        delete = Some(profile.name.clone());
+                    }
+                });
+                ui.label(format!("Userfolder: {}", profile.beamng_dir.display()));
+                if let Some(mods_dir) = &profile.mods_dir {
+                    ui.label(format!("Mods: {}", mods_dir.display()));
+                }
+                if let Some(presets_dir) = &profile.presets_dir {
+                    ui.label(format!("Presets: {}", presets_dir.display()));
+                }
+            }
+
+            ui.separator();
+            ui.label(RichText::new("New Profile").strong());
+            egui::Grid::new("new_profile").num_columns(2).show(ui, |ui| {
+                let draft = &mut app_data.new_profile;
+                ui.label("Name");
+                ui.text_edit_singleline(&mut draft.name);
+                ui.end_row();
+                ui.label("Userfolder");
+                ui.text_edit_singleline(&mut draft.beamng_dir)
+                    .on_hover_text("The BeamNG.drive folder holding one folder per game version");
+                ui.end_row();
+                ui.label("Mods folder");
+                ui.add(egui::TextEdit::singleline(&mut draft.mods_dir).hint_text("from the game version"));
+                ui.end_row();
+                ui.label("Presets folder");
+                ui.add(egui::TextEdit::singleline(&mut draft.presets_dir).hint_text("shared"));
+                ui.end_row();
+            });
+            if ui.button("Add Profile").clicked() {
+                match app_data.new_profile.build(&app_data.profiles) {
+                    Ok(profile) => {
+                        app_data.profiles.push(profile);
+                        app_data.new_profile = Default::default();
+                        app_data.profile_error = None;
+                    }
+                    Err(e) => app_data.profile_error = Some(e),
+                }
+            }
+            if let Some(error) = &app_data.profile_error {
+                ui.label(RichText::new(error).color(egui::Color32::from_rgb(200, 50, 50)));
+            }
+        });
+    if let Some(name) = delete {
+        app_data.profiles.retain(|p| p.name != name);
+        if app_data.active_profile.as_ref() == Some(&name) {
+            switch_to = Some(None);
+        }
+    }
+    if let Some(name) = switch_to {
+        app_data.switch_profile(name);
+    }
+    if !open {
+        app_data.profiles_open = false;
+    }
+}
+
+/// Switches between the game's version folders and opens the migration assistant.
+fn game_version_menu(ui: &mut egui::Ui, app_data: &mut App) {
+    let mut text = RichText::new(&app_data.beamng_version);
+    if app_data.beamng_version != app_data.game_version {
+        text = text.color(egui::Color32::from_rgb(200, 150, 50));
+    }
+    let response = ui.menu_button(text, |ui| {
+        let blocker = app_data.pending_work();
+        let mut picked = None;
+        for folder in &app_data.version_folders {
+            let mut label = folder.version.clone();
+            if folder.version == app_data.game_version {
+                label.push_str(" (game)");
+            }
+            let shown = folder.version == app_data.beamng_version;
+            let label = format!("{}: {}", label, folder.summary());
+            if ui
+                .add_enabled(
+                    !shown && blocker.is_none(),
+                    egui::Button::new(label).wrap_mode(egui::TextWrapMode::Extend),
+                )
+                .on_disabled_hover_text(blocker.unwrap_or("Already shown"))
+                .clicked()
+            {
+                picked = Some(folder.version.clone());
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui
+            .add_enabled(
+                app_data.version_folders.len() > 1 && blocker.is_none(),
+                egui::Button::new("Migrate Mods…"),
+            )
+            .on_disabled_hover_text(blocker.unwrap_or("There is no other version folder"))
+            .clicked()
+        {
+            app_data.migration_open = true;
+            ui.close_menu();
+        }
+        if let Some(version) = picked {
+            app_data.request_reload(Reload { version: Some(version) });
+        }
+    });
+    if app_data.beamng_version != app_data.game_version {
+        response
+            .response
+            .on_hover_text(format!("Showing an older version folder; the game runs as {}", app_data.game_version));
+    }
+}
+
+/// Copies or moves mods from another version folder into the one shown.
+pub fn migration_window(ctx: &egui::Context, app_data: &mut App) {
+    if !app_data.migration_open {
+        return;
+    }
+    let mut open = true;
+    let mut done = false;
+    let mut migrate = None;
+    egui::Window::new(format!("Migrate Mods into {}", app_data.beamng_version))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let others: Vec<_> = app_data
+                .version_folders
+                .iter()
+                .filter(|f| f.version != app_data.beamng_version)
+                .collect();
+            let selected = app_data.migration_source.clone().unwrap_or_else(|| "Choose…".to_owned());
+            ui.horizontal(|ui| {
+                ui.label("From version:");
+                egui::ComboBox::from_id_source("migration_source")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for folder in &others {
+                            ui.selectable_value(
+                                &mut app_data.migration_source,
+                                Some(folder.version.clone()),
+                                format!("{}  ({})", folder.version, folder.summary()),
+                            );
+                        }
+                    });
+            });
+            let source = others
+                .iter()
+                .find(|f| Some(&f.version) == app_data.migration_source.as_ref());
+            if let Some(source) = source {
+                ui.label(format!("{} contains {}.", source.version, source.summary()));
+            }
+            if let Some(current) = app_data
+                .version_folders
+                .iter()
+                .find(|f| f.version == app_data.beamng_version)
+            {
+                ui.label(format!("{} contains {}.", current.version, current.summary()));
+            }
+            ui.checkbox(&mut app_data.migration_carry_active, "Carry over which mods are active (db.json)");
+            ui.label(RichText::new("Archives the target already has are kept as they are.").small());
+            ui.separator();
+            match &app_data.migration_result {
+                Some(Ok(report)) => {
+                    ui.label(format!("Done: {}.", report.summary()));
+                    for error in &report.errors {
+                        ui.label(
+                            RichText::new(format!("Failed: {}", error)).color(egui::Color32::from_rgb(200, 50, 50)),
+                        );
+                    }
+                    done = ui.button("Close and Reload").clicked();
+                    return;
+                }
+                Some(Err(error)) => {
+                    ui.label(RichText::new(error).color(egui::Color32::from_rgb(200, 50, 50)));
+                }
+                None => {}
+            }
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(source.is_some() && !app_data.game_running, |ui| {
+                    if ui.button("Copy").clicked() {
+                        migrate = Some(TransferMode::Copy);
+                    }
+                    if ui
+                        .button("Move")
+                        .on_hover_text("Removes the archives from the old version folder")
+                        .clicked()
+                    {
+                        migrate = Some(TransferMode::Move);
+                    }
+                });
+            });
+        });
+    if let (Some(mode), Some(source)) = (migrate, app_data.migration_source.clone()) {
+        app_data.migrate_mods(&source, mode);
+    }
+    if !open || done {
+        app_data.finish_migration();
+    }
+}
+
+/// Asks before closing the window would lose something, see `App::pending_work`.
+pub fn quit_confirm_window(ctx: &egui::Context, app_data: &mut App) {
+    if !app_data.quit_confirm_open {
+        return;
+    }
+    let Some(reason) = app_data.pending_work() else {
+        // Resolved in the meantime, e.g. the game exited
+        app_data.quit_confirm_open = false;
+        return;
+    };
+    let mut quit = false;
+    egui::Window::new("Quit BeamMM.gui?")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(format!("{}. Quitting now loses them.", reason));
+            ui.horizontal(|ui| {
+                if app_data.play_session.is_some()
+                    && ui
+                        .button("Restore Now and Quit")
+                        .on_hover_text("Writes db.json even if the game is still running")
+                        .clicked()
+                {
+                    app_data.restore_play_session_now();
+                    quit = true;
+                } else if app_data.play_session.is_none()
+                    && app_data.queued_mod_states.is_some()
+                    && ui
+                        .button("Write Now and Quit")
+                        .on_hover_text("The game may overwrite these changes or get confused by them")
+                        .clicked()
+                {
+                    app_data.override_write_lock();
+                    quit = true;
+                }
+                if ui
+                    .button(RichText::new("Quit Anyway").color(egui::Color32::from_rgb(200, 50, 50)))
+                    .clicked()
+                {
+                    quit = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    app_data.quit_confirm_open = false;
+                }
+            });
+        });
+    if quit {
+        app_data.quit_confirmed = true;
+        app_data.quit_confirm_open = false;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+}
+
+/// Shown while the loader's worker thread is reading the mod folder and presets.
+pub fn loading_screen(ctx: &egui::Context, loader: &Loader) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 3.0);
+            ui.heading("BeamMM.gui");
+            ui.add_space(8.0);
+            ui.label(&loader.stage);
+
+            let progress_bar = if loader.total > 0 {
+                egui::ProgressBar::new(loader.done as f32 / loader.total as f32)
+                    .text(format!("{} / {}", loader.done, loader.total))
+            } else {
+                egui::ProgressBar::new(0.0).animate(true)
+            };
+            ui.add(progress_bar.desired_width(300.0));
+            ui.add_space(8.0);
+
+            if loader.is_cancelling() {
+                ui.label("Cancelling...");
+            } else if ui.button("Cancel").clicked() {
+                loader.cancel();
+            }
+        });
+    });
+}
+
+/// What the user picked on the load failed screen.
+pub enum LoadFailedChoice {
+    Retry,
+    /// Retry with the automatically found folders instead of the active profile's.
+    UseDefaultProfile,
+}
+
+/// Shown when loading failed or was cancelled. `profile` is the active profile, if any.
+pub fn load_failed_screen(ctx: &egui::Context, message: &str, profile: Option<&str>) -> Option<LoadFailedChoice> {
+    let mut choice = None;
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 3.0);
+            ui.heading("BeamMM.gui");
+            ui.add_space(8.0);
+            ui.label(RichText::new(message).color(egui::Color32::from_rgb(200, 50, 50)));
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("Retry").clicked() {
+                    choice = Some(LoadFailedChoice::Retry);
+                }
+                if let Some(profile) = profile {
+                    if ui
+                        .button("Use Default Profile")
+                        .on_hover_text(format!("Stop using profile {}", profile))
+                        .clicked()
+                    {
+                        choice = Some(LoadFailedChoice::UseDefaultProfile);
+                    }
+                }
+                if ui.button("Quit").clicked() {
+                    ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+                }
             });
         });
     });
+    choice
 }
 
 pub fn presets_panel(ctx: &egui::Context, app_data: &mut App) {
-    egui::SidePanel::right("presets_panel").show(ctx, |ui| {
+    let panel = egui::SidePanel::right("presets_panel")
+        .default_width(app_data.presets_panel_width.unwrap_or(200.0));
+    let panel_response = panel.show(ctx, |ui| {
         ui.heading("Presets");
         ui.horizontal(|_| {});
 
@@ -45,17 +553,26 @@ pub fn presets_panel(ctx: &egui::Context, app_data: &mut App) {
             };
         });
         let mut delete_preset = false;
-        if let Some(preset_name) = &app_data.current_preset {
+        if let Some(preset_name) = app_data.current_preset.clone() {
             if ui.button("Delete Preset").clicked() {
                 delete_preset = true;
             }
 
             // ui.label("Preset Mods");
 
+            preset_verified_label(ui, app_data, &preset_name);
+
+            let missing = app_data
+                .missing_preset_mods
+                .get(&preset_name)
+                .cloned()
+                .unwrap_or_default();
+            let mut replacement = None;
+
             let preset = &mut app_data
                 .presets
                 .iter_mut()
-                .find(|(name, _)| name == preset_name)
+                .find(|(name, _)| *name == preset_name)
                 .unwrap()
                 .1;
 
@@ -82,7 +599,38 @@ pub fn presets_panel(ctx: &egui::Context, app_data: &mut App) {
                                     }
                                 });
                                 row.col(|ui| {
-                                    ui.label(&*mod_name);
+                                    if !missing.contains(&mod_name) {
+                                        ui.label(&*mod_name);
+                                        return;
+                                    }
+                                    let text = RichText::new(format!("⚠ {}", mod_name))
+                                        .color(egui::Color32::from_rgb(200, 50, 50));
+                                    ui.menu_button(text, |ui| {
+                                        ui.label("Not installed. Replace with:");
+                                        let known_hash = app_data
+                                            .preset_meta
+                                            .settings(&preset_name)
+                                            .and_then(|s| s.hashes.get(&mod_name))
+                                            .map(String::as_str);
+                                        let candidates = find_replacements(
+                                            &mod_name,
+                                            known_hash,
+                                            preset.get_mods(),
+                                            &app_data.staged_mods,
+                                        );
+                                        if candidates.is_empty() {
+                                            ui.label("Nothing similar is installed");
+                                        }
+                                        for candidate in candidates {
+                                            let label = format!("{}  ({})", candidate.mod_name, candidate.reason.label());
+                                            if ui.button(label).clicked() {
+                                                replacement = Some((mod_name.clone(), candidate.mod_name));
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("Not installed, click to find a replacement");
                                 });
                             });
                         }
@@ -92,15 +640,52 @@ pub fn presets_panel(ctx: &egui::Context, app_data: &mut App) {
                     .save_to_path(&app_data.beam_paths.presets_dir)
                     .unwrap();
             });
+            if !mods_to_remove.is_empty() {
+                app_data.rebuild_preset_index();
+            }
+            if let Some((old, new)) = replacement {
+                app_data.replace_preset_mod(&preset_name, &old, &new);
+            }
+
+            preset_includes_component(ui, app_data, &preset_name);
+
+            let excludes = app_data.preset_meta.excludes(&preset_name).to_vec();
+            let mut exclude_to_remove = None;
+            egui::CollapsingHeader::new(format!("Must Be Disabled ({})", excludes.len()))
+                .id_source("preset_excludes")
+                .show(ui, |ui| {
+                    if excludes.is_empty() {
+                        ui.label(RichText::new("Add mods from their right-click menu.").small());
+                    }
+                    for mod_name in &excludes {
+                        ui.horizontal(|ui| {
+                            if ui.button("Remove").clicked() {
+                                exclude_to_remove = Some(mod_name.clone());
+                            }
+                            ui.label(mod_name);
+                        });
+                    }
+                });
+            if let Some(mod_name) = exclude_to_remove {
+                app_data.remove_preset_exclude(&preset_name, &mod_name);
+            }
+        }
+        ui.separator();
+        if ui.button("Exclusion Groups").clicked() {
+            app_data.exclusion_groups_open = true;
         }
         if delete_preset {
             if let Some(preset_name) = &app_data.current_preset {
-                Preset::delete(&preset_name, &app_data.beam_paths.presets_dir).unwrap();
+                Preset::delete(preset_name, &app_data.beam_paths.presets_dir).unwrap();
                 app_data.presets.retain(|(name, _)| name != preset_name);
+                app_data.preset_meta.forget_preset(preset_name);
             }
             app_data.current_preset = None;
+            app_data.save_preset_order();
+            app_data.rebuild_preset_index();
         }
     });
+    app_data.presets_panel_width = Some(panel_response.response.rect.width());
 }
 
 pub fn mods_panel(ctx: &egui::Context, app_data: &mut App) {
@@ -108,10 +693,187 @@ pub fn mods_panel(ctx: &egui::Context, app_data: &mut App) {
         ui.heading("Mods");
         ui.horizontal(|_| {});
         mod_actions_component(ui, app_data);
+        saved_views_component(ui, app_data);
         mods_table_component(ui, app_data);
     });
 }
 
+/// Dropdown of saved filter views, plus controls to save, delete and pick the startup default.
+fn saved_views_component(ui: &mut egui::Ui, app_data: &mut App) {
+    let mut view_to_apply = None;
+
+    ui.horizontal(|ui| {
+        ui.label("View:");
+
+        // Flag the selected view if the filters or sort have been changed since applying it
+        let modified = app_data.current_view.as_deref().is_some_and(|name| {
+            app_data.saved_views.get(name) != Some(&app_data.current_view_state(name))
+        });
+        let selected_text = match &app_data.current_view {
+            Some(name) if modified => format!("{} (modified)", name),
+            Some(name) => name.clone(),
+            None => "None".to_owned(),
+        };
+        egui::ComboBox::from_id_source("saved_view_combo")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for view in &app_data.saved_views.views {
+                    let is_default = app_data.saved_views.default_view.as_deref() == Some(&view.name);
+                    let label = if is_default {
+                        format!("{} (default)", view.name)
+                    } else {
+                        view.name.clone()
+                    };
+                    let is_current = app_data.current_view.as_deref() == Some(&view.name);
+                    if ui.selectable_label(is_current, label).clicked() {
+                        view_to_apply = Some(view.clone());
+                    }
+                }
+            });
+
+        if let Some(name) = app_data.current_view.clone() {
+            if modified && ui.button("Update").clicked() {
+                let view = app_data.current_view_state(&name);
+                app_data.saved_views.upsert(view);
+                app_data.saved_views.save(&app_data.beam_paths.beammm_dir);
+            }
+
+            let is_default = app_data.saved_views.default_view.as_deref() == Some(&name);
+            let mut make_default = is_default;
+            if ui.checkbox(&mut make_default, "Default").changed() {
+                app_data.saved_views.default_view = make_default.then(|| name.clone());
+                app_data.saved_views.save(&app_data.beam_paths.beammm_dir);
+            }
+
+            if ui.button("Delete").clicked() {
+                app_data.saved_views.remove(&name);
+                app_data.saved_views.save(&app_data.beam_paths.beammm_dir);
+                app_data.current_view = None;
+            }
+        }
+
+        ui.separator();
+
+        ui.add(
+            egui::TextEdit::singleline(&mut app_data.new_view_name)
+                .hint_text("new view name")
+                .desired_width(120.0),
+        );
+        let name = app_data.new_view_name.trim().to_owned();
+        if ui
+            .add_enabled(!name.is_empty(), egui::Button::new("Save View"))
+            .on_hover_text("Save the current search, filters and sort under this name")
+            .clicked()
+        {
+            let view = app_data.current_view_state(&name);
+            app_data.saved_views.upsert(view);
+            app_data.saved_views.save(&app_data.beam_paths.beammm_dir);
+            app_data.current_view = Some(name);
+            app_data.new_view_name.clear();
+        }
+    });
+
+    if let Some(view) = view_to_apply {
+        app_data.apply_view(&view);
+    }
+}
+
+/// Which game version the preset last had all of its mods installed with.
+fn preset_verified_label(ui: &mut egui::Ui, app_data: &App, preset_name: &str) {
+    let verified = app_data
+        .preset_meta
+        .settings(preset_name)
+        .and_then(|s| s.verified_version.as_deref());
+    let missing = app_data.missing_preset_mods.get(preset_name).map_or(0, Vec::len);
+    let text = match verified {
+        Some(version) if missing == 0 => RichText::new(format!("Verified with BeamNG {}", version)),
+        Some(version) => RichText::new(format!("{} mods missing, last verified with BeamNG {}", missing, version))
+            .color(egui::Color32::from_rgb(200, 150, 50)),
+        None => RichText::new(format!("{} mods missing, never verified", missing))
+            .color(egui::Color32::from_rgb(200, 150, 50)),
+    };
+    ui.label(text.small());
+}
+
+/// The presets `preset_name` includes, as a tree that expands into their own includes, with
+/// controls to add and remove direct includes.
+fn preset_includes_component(ui: &mut egui::Ui, app_data: &mut App, preset_name: &str) {
+    let meta = &app_data.preset_meta;
+    let includes = meta.includes(preset_name).to_vec();
+    let mut include_to_remove = None;
+    let mut include_to_add = None;
+    egui::CollapsingHeader::new(format!("Includes ({})", includes.len()))
+        .id_source("preset_includes")
+        .show(ui, |ui| {
+            for included in &includes {
+                ui.horizontal(|ui| {
+                    if ui.button("Remove").clicked() {
+                        include_to_remove = Some(included.clone());
+                    }
+                    preset_tree_node(ui, app_data, included, &mut vec![preset_name.to_owned()]);
+                });
+            }
+            ui.menu_button("Include Preset…", |ui| {
+                let candidates: Vec<&String> = app_data
+                    .presets
+                    .iter()
+                    .map(|(name, _)| name)
+                    .filter(|name| !includes.contains(name) && !meta.would_cycle(preset_name, name))
+                    .collect();
+                if candidates.is_empty() {
+                    ui.label("No other presets can be included");
+                }
+                for name in candidates {
+                    if ui.button(name).clicked() {
+                        include_to_add = Some(name.clone());
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+    if let Some(name) = include_to_remove {
+        app_data
+            .preset_meta
+            .settings_mut(preset_name)
+            .includes
+            .retain(|n| *n != name);
+        app_data.preset_rules_changed(preset_name);
+    }
+    if let Some(name) = include_to_add {
+        app_data.preset_meta.settings_mut(preset_name).includes.push(name);
+        app_data.preset_rules_changed(preset_name);
+    }
+}
+
+/// One included preset with its mod count, expandable into the presets it includes in turn.
+/// `path` holds the presets above it, so a cycle is shown instead of followed.
+fn preset_tree_node(ui: &mut egui::Ui, app_data: &App, preset_name: &str, path: &mut Vec<String>) {
+    let mod_count = app_data
+        .presets
+        .iter()
+        .find(|(name, _)| name == preset_name)
+        .map_or(0, |(_, p)| p.get_mods().len());
+    let label = format!("{}  ({} mods)", preset_name, mod_count);
+    if path.iter().any(|n| n == preset_name) {
+        ui.label(RichText::new(format!("{}  (cycle)", preset_name)).color(egui::Color32::from_rgb(200, 50, 50)));
+        return;
+    }
+    let includes = app_data.preset_meta.includes(preset_name);
+    if includes.is_empty() {
+        ui.label(label);
+        return;
+    }
+    path.push(preset_name.to_owned());
+    egui::CollapsingHeader::new(label)
+        .id_source(("preset_tree", path.join("/")))
+        .show(ui, |ui| {
+            for included in includes {
+                preset_tree_node(ui, app_data, included, path);
+            }
+        });
+    path.pop();
+}
+
 fn preset_select_component(ui: &mut egui::Ui, app_data: &mut App, preset_name: &mut String) {
     ui.menu_button(preset_name.clone(), |ui| {
         for preset in beammm::Preset::list(&app_data.beam_paths.presets_dir).unwrap() {
@@ -130,6 +892,7 @@ fn preset_select_component(ui: &mut egui::Ui, app_data: &mut App, preset_name: &
                     .save_to_path(&app_data.beam_paths.presets_dir)
                     .unwrap();
                 app_data.presets.push((new_preset_name.clone(), new_preset));
+                app_data.save_preset_order();
                 *preset_name = new_preset_name;
                 ui.close_menu();
             }
@@ -138,11 +901,16 @@ fn preset_select_component(ui: &mut egui::Ui, app_data: &mut App, preset_name: &
 }
 
 fn presets_table_component(ui: &mut egui::Ui, app_data: &mut App) {
-    ui.label("All Presets:");
+    let mut toggled = None;
+    let mut moved = None;
+    ui.label("All Presets:")
+        .on_hover_text("Presets are applied top to bottom; when two disagree the lower one wins. Drag ☰ to reorder.");
     TableBuilder::new(ui)
+        .column(Column::exact(16.0))
         .column(Column::exact(75.0))
         .column(Column::auto().resizable(false))
         .header(20.0, |mut header| {
+            header.col(|_| {});
             header.col(|ui| {
                 ui.add(egui::Label::new("Enabled").wrap_mode(egui::TextWrapMode::Extend));
             });
@@ -151,8 +919,15 @@ fn presets_table_component(ui: &mut egui::Ui, app_data: &mut App) {
             });
         })
         .body(|mut body| {
-            for (preset_name, preset) in &mut app_data.presets {
+            for (index, (preset_name, preset)) in app_data.presets.iter().enumerate() {
                 body.row(20.0, |mut row| {
+                    row.col(|ui| {
+                        ui.dnd_drag_source(egui::Id::new(("preset_drag", index)), index, |ui| {
+                            ui.label("☰");
+                        })
+                        .response
+                        .on_hover_text("Drag to change priority");
+                    });
                     row.col(|ui| {
                         let text = if preset.is_enabled() {
                             RichText::new("Enabled").color(egui::Color32::GREEN)
@@ -160,76 +935,197 @@ fn presets_table_component(ui: &mut egui::Ui, app_data: &mut App) {
                             RichText::new("Disabled").color(egui::Color32::RED)
                         };
                         if ui.button(text).clicked() {
-                            if preset.is_enabled() {
-                                preset.disable(&mut app_data.beam_mod_config).unwrap();
-                            } else {
-                                preset.enable();
-                            }
-                            preset
-                                .save_to_path(&app_data.beam_paths.presets_dir)
-                                .unwrap();
-                            app_data
-                                .beam_mod_config
-                                .apply_presets(&app_data.beam_paths.presets_dir)
-                                .unwrap();
-                            app_data
-                                .beam_mod_config
-                                .save_to_path(&app_data.beam_paths.mods_dir)
-                                .unwrap();
+                            toggled = Some(preset_name.clone());
                         }
                     });
                     row.col(|ui| {
-                        ui.label(&*preset_name);
+                        let includes = app_data.preset_meta.includes(preset_name);
+                        if includes.is_empty() {
+                            ui.label(&*preset_name);
+                        } else {
+                            ui.label(format!("{} (+{})", preset_name, includes.len()))
+                                .on_hover_text(format!("Includes {}", includes.join(", ")));
+                        }
+                        if let Some(missing) = app_data.missing_preset_mods.get(preset_name) {
+                            ui.label(RichText::new("⚠").color(egui::Color32::from_rgb(200, 150, 50)))
+                                .on_hover_text(format!("{} mods aren't installed", missing.len()));
+                        }
                     });
+
+                    // Dropping a dragged preset on a row moves it to that row's position
+                    let response = row.response();
+                    if let Some(from) = response.dnd_hover_payload::<usize>() {
+                        if *from != index {
+                            let y = if *from < index { response.rect.bottom() } else { response.rect.top() };
+                            let stroke = response.ctx.style().visuals.selection.stroke;
+                            response.ctx.layer_painter(response.layer_id).hline(response.rect.x_range(), y, stroke);
+                        }
+                    }
+                    if let Some(from) = response.dnd_release_payload::<usize>() {
+                        moved = Some((*from, index));
+                    }
                 });
             }
         });
+    if let Some((from, to)) = moved {
+        app_data.move_preset(from, to);
+    }
+    if let Some(preset_name) = toggled {
+        app_data.request_preset_toggle(&preset_name);
+    }
 }
 
-fn mods_table_component(ui: &mut egui::Ui, app_data: &mut App) {
-    // Search bar (kept visible) and a collapsible Advanced Filters section
-    ui.horizontal(|ui| {
-        ui.label("Search mods: ");
-        ui.text_edit_singleline(&mut app_data.mod_search_query);
-    });
-
-    // Collapsible advanced filters; remember and persist open/closed state
-    let collapsing_resp = egui::CollapsingHeader::new("Advanced Filters")
-        .id_source("advanced_filters")
-        .default_open(app_data.advanced_filters_open)
-        .show(ui, |ui| {
-            ui.horizontal(|ui| {
-                ui.label("Fullpath: ");
-                ui.text_edit_singleline(&mut app_data.fullpath_filter);
+/// Shows what toggling a preset will change and applies it once confirmed.
+pub fn preset_confirm_window(ctx: &egui::Context, app_data: &mut App) {
+    let Some(plan) = &app_data.pending_preset_plan else {
+        return;
+    };
+    let verb = if plan.enable { "Enable" } else { "Disable" };
+    let mut confirmed = false;
+    let mut cancelled = false;
+    egui::Window::new(format!("{} preset '{}'", verb, plan.preset))
+        .collapsible(false)
+        .resizable(true)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            if let Some(cycle) = &plan.cycle {
+                ui.label(
+                    RichText::new(format!(
+                        "This preset includes itself ({}). Remove one of those includes first.",
+                        cycle.join(" → ")
+                    ))
+                    .color(egui::Color32::from_rgb(200, 50, 50)),
+                );
+            }
+            if !plan.disable_presets.is_empty() {
+                ui.label(
+                    RichText::new(format!(
+                        "Exclusion groups will disable: {}",
+                        plan.disable_presets.join(", ")
+                    ))
+                    .color(egui::Color32::from_rgb(200, 150, 50)),
+                );
+            }
+            if plan.activate.is_empty() && plan.deactivate.is_empty() {
+                ui.label("No mods will change state.");
+            }
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                mod_list_section(ui, "Will be activated", &plan.activate, egui::Color32::from_rgb(50, 200, 50));
+                mod_list_section(ui, "Will be deactivated", &plan.deactivate, egui::Color32::from_rgb(200, 50, 50));
+                if !plan.shared.is_empty() {
+                    egui::CollapsingHeader::new(format!("Shared with other enabled presets ({})", plan.shared.len()))
+                        .show(ui, |ui| {
+                            for (mod_name, presets) in &plan.shared {
+                                ui.label(format!("{}  ({})", mod_name, presets.join(", ")));
+                            }
+                        });
+                }
+                mod_list_section(ui, "Not installed, will be skipped (replace them under Edit Preset)", &plan.missing, egui::Color32::from_rgb(200, 150, 50));
             });
-
+            ui.separator();
             ui.horizontal(|ui| {
-                ui.label("Type: ");
-                egui::ComboBox::from_id_source("mod_type_combo")
-                    .selected_text(if app_data.mod_type_filter.is_empty() { "All" } else { &app_data.mod_type_filter })
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(&mut app_data.mod_type_filter, String::new(), "All");
-                        for t in &app_data.available_mod_types {
-                            ui.selectable_value(&mut app_data.mod_type_filter, t.clone(), t);
-                        }
-                    });
+                if ui.add_enabled(plan.cycle.is_none(), egui::Button::new(verb)).clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+    if confirmed {
+        if let Some(plan) = app_data.pending_preset_plan.take() {
+            app_data.apply_preset_plan(&plan);
+        }
+    } else if cancelled || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+        app_data.pending_preset_plan = None;
+    }
+}
 
-                ui.separator();
+/// Warns before disabling mods that enabled presets require, see `App::request_set_active`.
+pub fn disable_required_window(ctx: &egui::Context, app_data: &mut App) {
+    if app_data.pending_disable.is_empty() {
+        return;
+    }
+    let (required, optional): (Vec<String>, Vec<String>) = app_data
+        .pending_disable
+        .iter()
+        .cloned()
+        .partition(|m| app_data.required_by.contains_key(m));
 
-                // Filter options
-                if ui.selectable_label(app_data.filter_active_only, "Active Only").clicked() {
-                    app_data.filter_active_only = !app_data.filter_active_only;
-                    app_data.filter_inactive_only = false;
+    let mut choice = None;
+    egui::Window::new("Disable required mods?")
+        .collapsible(false)
+        .resizable(true)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label("These mods are required by enabled presets:");
+            egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                for mod_name in &required {
+                    ui.label(format!("{}  (required by {})", mod_name, app_data.required_by[mod_name].join(", ")));
+                }
+            });
+            ui.label(
+                RichText::new("If you disable them they stay off until one of those presets is applied again.")
+                    .small(),
+            );
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .button(RichText::new("Disable All").color(egui::Color32::from_rgb(200, 50, 50)))
+                    .clicked()
+                {
+                    choice = Some(app_data.pending_disable.clone());
                 }
-                if ui.selectable_label(app_data.filter_inactive_only, "Inactive Only").clicked() {
-                    app_data.filter_inactive_only = !app_data.filter_inactive_only;
-                    app_data.filter_active_only = false;
+                if !optional.is_empty()
+                    && ui
+                        .button(format!("Only the {} Not Required", optional.len()))
+                        .clicked()
+                {
+                    choice = Some(optional.clone());
                 }
-                if ui.selectable_label(app_data.filter_selected_only, "Selected Only").clicked() {
-                    app_data.filter_selected_only = !app_data.filter_selected_only;
+                if ui.button("Cancel").clicked() {
+                    choice = Some(Vec::new());
                 }
             });
         });
+    if let Some(mod_names) = choice {
+        app_data.pending_disable.clear();
+        if !mod_names.is_empty() {
+            app_data.set_mods_active(&mod_names, false);
+        }
+    }
+}
+
+/// A collapsible, counted list of mod names; nothing if the list is empty.
+fn mod_list_section(ui: &mut egui::Ui, title: &str, mod_names: &[String], color: egui::Color32) {
+    if mod_names.is_empty() {
+        return;
+    }
+    egui::CollapsingHeader::new(RichText::new(format!("{} ({})", title, mod_names.len())).color(color))
+        .default_open(mod_names.len() <= 20)
+        .show(ui, |ui| {
+            for mod_name in mod_names {
+                ui.label(mod_name);
+            }
+        });
+}
+
+fn mods_table_component(ui: &mut egui::Ui, app_data: &mut App) {
+    // Any edit to the filter controls below invalidates the memoized view
+    let filter_before = app_data.filter.clone();
+
+    // Search bar (kept visible) and a collapsible Advanced Filters section
+    search_component(ui, app_data);
+
+    // Collapsible advanced filters; remember and persist open/closed state
+    let collapsing_resp = egui::CollapsingHeader::new("Advanced Filters")
+        .id_source("advanced_filters")
+        .default_open(app_data.advanced_filters_open)
+        .show(ui, |ui| advanced_filters_component(ui, app_data));
+
+    if app_data.filter != filter_before {
+        app_data.needs_filter = true;
+    }
 
     // Persist open/closed state if changed
     let is_open = collapsing_resp.openness > 0.5;
@@ -237,294 +1133,939 @@ fn mods_table_component(ui: &mut egui::Ui, app_data: &mut App) {
         app_data.advanced_filters_open = is_open;
         app_data.save_gui_config();
     }
-      // Sort controls
     ui.horizontal(|ui| {
-        ui.label(RichText::new("Sort by:"));
-        
-        // Direction toggle
-        if ui.button(if app_data.sort_ascending { "↑" } else { "↓" }).clicked() {
-            app_data.sort_ascending = !app_data.sort_ascending;
-            app_data.needs_sort = true;
-        }        // Sort options
-        ui.horizontal(|ui| {
-            if ui.selectable_label(app_data.sort_option == SortOption::Name, "Name").clicked() {
-                app_data.sort_option = SortOption::Name;
-                app_data.needs_sort = true;
-            }
-            if ui.selectable_label(app_data.sort_option == SortOption::Status, "Status").clicked() {
-                app_data.sort_option = SortOption::Status;
-                app_data.needs_sort = true;
-            }
-            if ui.selectable_label(app_data.sort_option == SortOption::Selection, "Selection").clicked() {
-                app_data.sort_option = SortOption::Selection;
-                app_data.needs_sort = true;
-            }
-            if ui.selectable_label(app_data.sort_option == SortOption::Date, "Date Added").clicked() {
-                app_data.sort_option = SortOption::Date;
-                app_data.needs_sort = true;
-            }
-            if ui.selectable_label(app_data.sort_option == SortOption::Filename, "Filename").clicked() {
-                app_data.sort_option = SortOption::Filename;
-                app_data.needs_sort = true;
-            }
-            if ui.selectable_label(app_data.sort_option == SortOption::Fullpath, "Fullpath").clicked() {
-                app_data.sort_option = SortOption::Fullpath;
-                app_data.needs_sort = true;
-            }
-            if ui.selectable_label(app_data.sort_option == SortOption::ModType, "Mod Type").clicked() {
-                app_data.sort_option = SortOption::ModType;
-                app_data.needs_sort = true;
-            }
+        ui.label(RichText::new("Click a column header to sort, shift-click to add it as a secondary key.").small());
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.menu_button("Columns", |ui| columns_menu(ui, app_data));
         });
-          // Apply sorting only when needed
-        if app_data.needs_sort {
-            app_data.staged_mods.sort_by(|a, b| {
-                let cmp = match app_data.sort_option {
-                    SortOption::Name => a.mod_name.cmp(&b.mod_name),
-                    SortOption::Status => {
-                        let a_active = app_data.beam_mod_config.is_mod_active(&a.mod_name).unwrap();
-                        let b_active = app_data.beam_mod_config.is_mod_active(&b.mod_name).unwrap();
-                        a_active.cmp(&b_active)
-                    },
-                    SortOption::Selection => a.selected.cmp(&b.selected),
-                    SortOption::Date => match (a.createtime, b.createtime) {
-                        (Some(a_time), Some(b_time)) => a_time.cmp(&b_time),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => a.mod_name.cmp(&b.mod_name), // fallback to name sorting
-                    },
-                    SortOption::Filename => {
-                        let a_val = a.filename.as_deref().unwrap_or("");
-                        let b_val = b.filename.as_deref().unwrap_or("");
-                        a_val.cmp(b_val)
+    });
+
+    // Sorting and filtering are memoized; this is a no-op unless an input changed
+    app_data.update_mod_view();
+    mods_table_keys(ui, app_data);
+
+    let ctx = ui.ctx().clone();
+    let modifiers = ui.input(|i| i.modifiers);
+    let cursor_row = app_data.cursor.as_deref().and_then(|name| app_data.row_of(name));
+    let columns = app_data.columns.visible();
+    let mut table = TableBuilder::new(ui)
+        .striped(true)
+        .sense(egui::Sense::click())
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center));
+    if let Some(row) = cursor_row.filter(|_| app_data.scroll_to_cursor) {
+        table = table.scroll_to_row(row, None);
+    }
+    app_data.scroll_to_cursor = false;
+    for column in &columns {
+        table = table.column(column.table_column());
+    }
+    table.header(20.0, |mut header| {
+            for &column in &columns {
+                header.col(|ui| match column.sort_option() {
+                    Some(_) if column == ModColumn::Name && app_data.ranked_by_relevance() => {
+                        ui.add(egui::Label::new("Mod Name (Relevance)").wrap_mode(egui::TextWrapMode::Truncate))
+                            .on_hover_text("Ranked by search relevance; the sort keys break ties");
                     }
-                    SortOption::Fullpath => {
-                        let a_val = a.fullpath.as_deref().unwrap_or("");
-                        let b_val = b.fullpath.as_deref().unwrap_or("");
-                        a_val.cmp(b_val)
+                    Some(option) => sortable_header(ui, app_data, column.label(), option),
+                    None => {
+                        ui.add(egui::Label::new(RichText::new(column.label()).strong()).wrap_mode(egui::TextWrapMode::Truncate));
                     }
-                    SortOption::ModType => {
-                        let a_val = a.mod_type.as_deref().unwrap_or("");
-                        let b_val = b.mod_type.as_deref().unwrap_or("");
-                        a_val.cmp(b_val)
+                });
+            }
+        })
+        .body(|body| {
+            let mut toggled = None;
+            let mut checkbox_clicked = None;
+            let mut row_clicked = None;
+            let mut open_details = None;
+            let mut context_action = None;
+            let mut chip_clicked = None;
+            // Visible part of the table body, taken from a cell
+            let mut body_clip = egui::Rect::EVERYTHING;
+
+            // Fuzzy words to highlight in the Mod Name column
+            let fuzzy_words = app_data
+                .search
+                .expr
+                .as_ref()
+                .map(|e| e.fuzzy_words())
+                .unwrap_or_default();
+
+            // Only the rows scrolled into view are laid out; `filtered_mods` maps row index to mod
+            let filtered_mods = &app_data.filtered_mods;
+            let presets = &app_data.presets;
+            let preset_index = &app_data.preset_index;
+            let exclusion_groups = &app_data.preset_meta.exclusion_groups;
+            let required_by = &app_data.required_by;
+            let staged_mods = &mut app_data.staged_mods;
+            body.rows(20.0, filtered_mods.len(), |mut row| {
+                let row_index = row.index();
+                let i = filtered_mods[row_index];
+                let staged_mod = &mut staged_mods[i];
+                row.set_selected(staged_mod.selected);
+                for &column in &columns {
+                    row.col(|ui| match column {
+                        ModColumn::Select => {
+                            if ui.checkbox(&mut staged_mod.selected, "").changed() {
+                                checkbox_clicked = Some(row_index);
+                            }
+                        }
+                        ModColumn::Active => {
+                            let text = if staged_mod.active {
+                                RichText::new("Active").color(egui::Color32::from_rgb(50, 200, 50))
+                            } else {
+                                RichText::new("Inactive").color(egui::Color32::from_rgb(200, 50, 50))
+                            };
+                            let mut response = ui.button(text);
+                            if let Some(presets) = required_by.get(&staged_mod.mod_name) {
+                                response = response.on_hover_text(if staged_mod.active {
+                                    format!("Required by preset {}", presets.join(", "))
+                                } else {
+                                    format!(
+                                        "Turned off, but enabled preset {} includes it; re-applying the preset turns it back on",
+                                        presets.join(", ")
+                                    )
+                                });
+                            }
+                            if response.clicked() {
+                                toggled = Some(i);
+                            }
+                        }
+                        ModColumn::Name => {
+                            body_clip = ui.clip_rect();
+                            let name = highlighted_text(ui, &staged_mod.mod_name, &fuzzy_words);
+                            // Not selectable, so clicks fall through to the row
+                            ui.add(
+                                egui::Label::new(name)
+                                    .wrap_mode(egui::TextWrapMode::Truncate)
+                                    .selectable(false),
+                            )
+                            .on_hover_text(&staged_mod.mod_name);
+                        }
+                        ModColumn::Presets => {
+                            if let Some(names) = preset_index.get(&staged_mod.mod_name) {
+                                if let Some(name) = preset_chips(ui, names) {
+                                    chip_clicked = Some(name);
+                                }
+                            }
+                        }
+                        _ => truncated_label(ui, &column_text(staged_mod, column)),
+                    });
+                }
+
+                let response = row.response();
+                response.context_menu(|ui| {
+                    // Right-clicking a selected row acts on the shown part of the selection
+                    let targets: Vec<String> = if staged_mods[i].selected {
+                        filtered_mods
+                            .iter()
+                            .map(|&j| &staged_mods[j])
+                            .filter(|m| m.selected)
+                            .map(|m| m.mod_name.clone())
+                            .collect()
+                    } else {
+                        vec![staged_mods[i].mod_name.clone()]
+                    };
+                    if let Some(action) = mod_context_menu(ui, presets, exclusion_groups, &targets) {
+                        context_action = Some((action, targets));
+                        ui.close_menu();
                     }
-                };
-                
-                if app_data.sort_ascending {
-                    cmp
-                } else {
-                    cmp.reverse()
+                });
+                if response.double_clicked() {
+                    open_details = Some(i);
+                } else if response.clicked() {
+                    row_clicked = Some(row_index);
+                }
+                if cursor_row == Some(row_index) {
+                    cursor_outline(&response, body_clip);
                 }
             });
-            app_data.needs_sort = false;
+
+            if let Some(i) = toggled {
+                app_data.toggle_active(i);
+            }
+            if let Some(row) = checkbox_clicked {
+                // The checkbox already toggled itself; shift extends it to a range
+                if modifiers.shift {
+                    app_data.select_range_to(row);
+                } else {
+                    app_data.selection_anchor = Some(app_data.staged_mods[app_data.filtered_mods[row]].mod_name.clone());
+                }
+                app_data.cursor = Some(app_data.staged_mods[app_data.filtered_mods[row]].mod_name.clone());
+                app_data.needs_filter = true;
+            }
+            if let Some(row) = row_clicked {
+                app_data.click_row(row, modifiers);
+            }
+            if let Some(i) = open_details {
+                app_data.details_mod = Some(app_data.staged_mods[i].mod_name.clone());
+            }
+            if let Some(name) = chip_clicked {
+                app_data.filter.preset = PresetFilter::In(name);
+                app_data.needs_filter = true;
+            }
+            if let Some((action, targets)) = context_action {
+                apply_mod_action(&ctx, app_data, action, targets);
+            }
+        });
+}
+
+/// Something picked from a mod row's context menu.
+enum ModAction {
+    SetActive(bool),
+    AddToPreset(String),
+    RemoveFromPreset(String),
+    ExcludeInPreset(String),
+    /// Index of an existing exclusion group, or `None` for a new one.
+    AddToExclusionGroup(Option<usize>),
+    Reveal,
+    CopyNames,
+    CopyPaths,
+    ShowConflicts,
+    Uninstall,
+}
+
+/// Context menu for one or more mods. Returns the picked action, if any.
+fn mod_context_menu(
+    ui: &mut egui::Ui,
+    presets: &[(String, Preset)],
+    exclusion_groups: &[ExclusionGroup],
+    targets: &[String],
+) -> Option<ModAction> {
+    let mut action = None;
+    let heading = match targets {
+        [mod_name] => mod_name.clone(),
+        _ => format!("{} selected mods", targets.len()),
+    };
+    ui.label(RichText::new(heading).strong());
+    ui.separator();
+
+    if ui.button("Enable").clicked() {
+        action = Some(ModAction::SetActive(true));
+    }
+    if ui.button("Disable").clicked() {
+        action = Some(ModAction::SetActive(false));
+    }
+    ui.separator();
+
+    ui.menu_button("Add to preset", |ui| {
+        if let Some(name) = preset_picker(ui, presets, targets, true) {
+            action = Some(ModAction::AddToPreset(name));
         }
     });
+    ui.menu_button("Remove from preset", |ui| {
+        if let Some(name) = preset_picker(ui, presets, targets, false) {
+            action = Some(ModAction::RemoveFromPreset(name));
+        }
+    });
+    ui.menu_button("Must be disabled by preset", |ui| {
+        if presets.is_empty() {
+            ui.label("No presets");
+        }
+        for (name, _) in presets {
+            if ui.button(name).clicked() {
+                action = Some(ModAction::ExcludeInPreset(name.clone()));
+                ui.close_menu();
+            }
+        }
+    });
+    ui.menu_button("Add to exclusion group", |ui| {
+        for (index, group) in exclusion_groups.iter().enumerate() {
+            if ui.button(&group.name).clicked() {
+                action = Some(ModAction::AddToExclusionGroup(Some(index)));
+                ui.close_menu();
+            }
+        }
+        if ui.button("New group").clicked() {
+            action = Some(ModAction::AddToExclusionGroup(None));
+            ui.close_menu();
+        }
+    });
+    ui.separator();
 
-    TableBuilder::new(ui)
-        .striped(true)
-        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-    // Make columns resizable so the user can drag to adjust widths
-    // Limit the number of resizable columns to reduce layout recalculation and dragging lag
-    .column(Column::auto().resizable(false))
-    .column(Column::exact(75.0).resizable(false))
-    .column(Column::remainder().resizable(true))
-    .column(Column::initial(250.0).resizable(true))
-    .column(Column::initial(100.0).resizable(false))
-    .header(20.0, |mut header| {
-            // Select column header
-            header.col(|ui| {
-                let text = if app_data.sort_option == SortOption::Selection {
-                    if app_data.sort_ascending { RichText::new("Select ↑") } else { RichText::new("Select ↓") }
-                } else { RichText::new("Select") };
-                ui.add(egui::Label::new(text).wrap_mode(egui::TextWrapMode::Truncate));
-            });
+    if ui.button("Reveal in file manager").clicked() {
+        action = Some(ModAction::Reveal);
+    }
+    let plural = if targets.len() > 1 { "s" } else { "" };
+    if ui.button(format!("Copy name{}", plural)).clicked() {
+        action = Some(ModAction::CopyNames);
+    }
+    if ui.button(format!("Copy path{}", plural)).clicked() {
+        action = Some(ModAction::CopyPaths);
+    }
+    if ui.button("Show conflicts").clicked() {
+        action = Some(ModAction::ShowConflicts);
+    }
+    ui.separator();
 
-            // Active column header
-            header.col(|ui| {
-                let text = if app_data.sort_option == SortOption::Status {
-                    if app_data.sort_ascending { RichText::new("Active ↑") } else { RichText::new("Active ↓") }
-                } else { RichText::new("Active") };
-                ui.add(egui::Label::new(text).wrap_mode(egui::TextWrapMode::Truncate));
-            });
+    if ui
+        .button(RichText::new("Uninstall…").color(egui::Color32::from_rgb(200, 50, 50)))
+        .clicked()
+    {
+        action = Some(ModAction::Uninstall);
+    }
+    action
+}
 
-            // Mod name column header (supports Name or Date sort indicator)
-            header.col(|ui| {
-                let text = if app_data.sort_option == SortOption::Name {
-                    if app_data.sort_ascending { RichText::new("Mod Name ↑") } else { RichText::new("Mod Name ↓") }
-                } else if app_data.sort_option == SortOption::Date {
-                    if app_data.sort_ascending { RichText::new("Mod Name (Date ↑)") } else { RichText::new("Mod Name (Date ↓)") }
-                } else { RichText::new("Mod Name") };
-                ui.add(egui::Label::new(text).wrap_mode(egui::TextWrapMode::Truncate));
+/// Buttons for the presets `targets` can be added to (`adding`) or removed from. Presets
+/// that already contain all of them, or none of them, are left out accordingly.
+fn preset_picker(ui: &mut egui::Ui, presets: &[(String, Preset)], targets: &[String], adding: bool) -> Option<String> {
+    let mut picked = None;
+    let mut any = false;
+    for (name, preset) in presets {
+        let members = targets.iter().filter(|m| preset.get_mods().contains(m)).count();
+        let applies = if adding { members < targets.len() } else { members > 0 };
+        if !applies {
+            continue;
+        }
+        any = true;
+        let label = if adding {
+            format!("{} (+{})", name, targets.len() - members)
+        } else {
+            format!("{} (-{})", name, members)
+        };
+        if ui.button(label).clicked() {
+            picked = Some(name.clone());
+            ui.close_menu();
+        }
+    }
+    if !any {
+        ui.label(if presets.is_empty() {
+            "No presets"
+        } else if adding {
+            "Already in every preset"
+        } else {
+            "Not in any preset"
+        });
+    }
+    picked
+}
+
+fn apply_mod_action(ctx: &egui::Context, app_data: &mut App, action: ModAction, targets: Vec<String>) {
+    let paths = || {
+        app_data
+            .staged_mods
+            .iter()
+            .filter(|m| targets.contains(&m.mod_name))
+            .filter_map(|m| app_data.mod_path(m))
+            .collect::<Vec<_>>()
+    };
+    match action {
+        ModAction::SetActive(active) => app_data.request_set_active(&targets, active),
+        ModAction::AddToPreset(preset_name) => app_data.add_mods_to_preset(&preset_name, &targets),
+        ModAction::RemoveFromPreset(preset_name) => {
+            app_data.remove_mods_from_preset(&preset_name, &targets)
+        }
+        ModAction::ExcludeInPreset(preset_name) => app_data.add_preset_excludes(&preset_name, &targets),
+        ModAction::AddToExclusionGroup(group) => {
+            let members = targets.into_iter().map(GroupMember::Mod).collect();
+            app_data.add_to_exclusion_group(group, members);
+        }
+        // Opening one file manager window per mod would be a nuisance
+        ModAction::Reveal => match paths().first() {
+            Some(path) => crate::platform::reveal_in_file_manager(path),
+            None => eprintln!("Don't know where {} is installed", targets[0]),
+        },
+        ModAction::CopyNames => ctx.copy_text(targets.join("\n")),
+        ModAction::CopyPaths => ctx.copy_text(
+            paths()
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        ModAction::ShowConflicts => app_data.conflicts_for = targets,
+        ModAction::Uninstall => app_data.pending_uninstall = targets,
+    }
+}
+
+/// Lists the mods that conflict with each mod in `App::conflicts_for`.
+pub fn conflicts_window(ctx: &egui::Context, app_data: &mut App) {
+    if app_data.conflicts_for.is_empty() {
+        return;
+    }
+    let mut open = true;
+    let mut show_details = None;
+    egui::Window::new("Conflicts")
+        .open(&mut open)
+        .resizable(true)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for mod_name in &app_data.conflicts_for {
+                    let Some(target) = app_data.staged_mods.iter().find(|m| &m.mod_name == mod_name) else {
+                        continue;
+                    };
+                    ui.label(RichText::new(mod_name).strong());
+                    let conflicts = conflicts_of(&app_data.staged_mods, target);
+                    if conflicts.is_empty() {
+                        ui.label("No conflicts found");
+                    }
+                    for conflict in conflicts {
+                        ui.horizontal(|ui| {
+                            if ui.link(&conflict.other.mod_name).clicked() {
+                                show_details = Some(conflict.other.mod_name.clone());
+                            }
+                            ui.label(RichText::new(format!("({})", conflict.reason.label())).small());
+                            if let Some(winner) = conflict_winner(app_data, target, conflict.other) {
+                                ui.label(RichText::new(format!("{} wins", winner)).small())
+                                    .on_hover_text("By the priority of the presets containing each mod");
+                            }
+                        });
+                    }
+                    ui.add_space(4.0);
+                }
             });
+        });
+    if show_details.is_some() {
+        app_data.details_mod = show_details;
+    }
+    if !open {
+        app_data.conflicts_for.clear();
+    }
+}
 
+/// Which of two conflicting mods takes precedence: the one in the higher-priority preset.
+/// `None` if neither is in a preset or they tie.
+fn conflict_winner<'a>(app_data: &App, a: &'a StagedMod, b: &'a StagedMod) -> Option<&'a str> {
+    match (app_data.mod_priority(&a.mod_name), app_data.mod_priority(&b.mod_name)) {
+        (Some(pa), Some(pb)) if pa == pb => None,
+        (Some(pa), Some(pb)) => Some(if pa > pb { &a.mod_name } else { &b.mod_name }),
+        (Some(_), None) => Some(&a.mod_name),
+        (None, Some(_)) => Some(&b.mod_name),
+        (None, None) => None,
+    }
+}
 
-            // Fullpath header
-            header.col(|ui| {
-                let text = if app_data.sort_option == SortOption::Fullpath {
-                    if app_data.sort_ascending { RichText::new("Fullpath ↑") } else { RichText::new("Fullpath ↓") }
-                } else { RichText::new("Fullpath") };
-                ui.add(egui::Label::new(text).wrap_mode(egui::TextWrapMode::Truncate));
+/// Asks before `App::pending_uninstall` is deleted from disk, then shows whatever went wrong.
+pub fn uninstall_confirm_window(ctx: &egui::Context, app_data: &mut App) {
+    if app_data.pending_uninstall.is_empty() {
+        uninstall_report_window(ctx, app_data);
+        return;
+    }
+    let mut confirmed = false;
+    let mut cancelled = false;
+    egui::Window::new("Uninstall mods")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "Delete {} mod(s) from the mods folder? This can't be undone.",
+                app_data.pending_uninstall.len()
+            ));
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for mod_name in &app_data.pending_uninstall {
+                    ui.label(mod_name);
+                }
+            });
+            let presets = app_data.presets_containing(&app_data.pending_uninstall);
+            if !presets.is_empty() {
+                ui.separator();
+                ui.label(format!("Listed in presets: {}", presets.join(", ")));
+                ui.checkbox(
+                    &mut app_data.uninstall_from_presets,
+                    "Also remove them from these presets",
+                )
+                .on_hover_text("Otherwise the presets stay incomplete, with the mods shown as missing");
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .button(RichText::new("Uninstall").color(egui::Color32::from_rgb(200, 50, 50)))
+                    .clicked()
+                {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
             });
+        });
+    if confirmed {
+        let mod_names = std::mem::take(&mut app_data.pending_uninstall);
+        let from_presets = app_data.uninstall_from_presets;
+        app_data.uninstall_mods(&mod_names, from_presets);
+    } else if cancelled {
+        app_data.pending_uninstall.clear();
+    }
+}
 
-            // Type header
-            header.col(|ui| {
-                let text = if app_data.sort_option == SortOption::ModType {
-                    if app_data.sort_ascending { RichText::new("Type ↑") } else { RichText::new("Type ↓") }
-                } else { RichText::new("Type") };
-                ui.label(text);
+fn uninstall_report_window(ctx: &egui::Context, app_data: &mut App) {
+    if app_data.uninstall_report.is_empty() {
+        return;
+    }
+    let mut dismissed = false;
+    egui::Window::new("Uninstall mods")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label("Not everything went as planned:");
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for line in &app_data.uninstall_report {
+                    ui.label(RichText::new(line).color(egui::Color32::from_rgb(200, 50, 50)));
+                }
             });
-        })
-        .body(|mut body| {            // First collect mod statuses
-            let mod_statuses: Vec<_> = app_data.staged_mods.iter().map(|m| (
-                m.mod_name.clone(),
-                app_data.beam_mod_config.is_mod_active(&m.mod_name).unwrap()
-            )).collect();
-
-            let filtered_mods = app_data.staged_mods.iter_mut().enumerate().filter(|(i, m)| {
-                // Text search filter
-                let text_matches = m.mod_name
-                    .to_lowercase()
-                    .contains(&app_data.mod_search_query.to_lowercase());
-                // filename/fullpath/type filters
-                let filename_matches = if app_data.filename_filter.is_empty() {
-                    true
-                } else {
-                    m.filename
-                        .as_deref()
-                        .unwrap_or("")
-                        .to_lowercase()
-                        .contains(&app_data.filename_filter.to_lowercase())
-                };
+            if ui.button("OK").clicked() {
+                dismissed = true;
+            }
+        });
+    if dismissed {
+        app_data.uninstall_report.clear();
+    }
+}
 
-                let fullpath_matches = if app_data.fullpath_filter.is_empty() {
-                    true
-                } else {
-                    m.fullpath
-                        .as_deref()
-                        .unwrap_or("")
-                        .to_lowercase()
-                        .contains(&app_data.fullpath_filter.to_lowercase())
-                };
+/// Outline the row under the keyboard cursor, without spilling over the table header.
+fn cursor_outline(response: &egui::Response, clip: egui::Rect) {
+    let stroke = response.ctx.style().visuals.selection.stroke;
+    response
+        .ctx
+        .layer_painter(response.layer_id)
+        .with_clip_rect(clip)
+        .rect_stroke(response.rect.shrink(1.0), 2.0, stroke);
+}
 
-                let modtype_matches = if app_data.mod_type_filter.is_empty() {
-                    true
-                } else {
-                    m.mod_type.as_deref().unwrap_or("") == app_data.mod_type_filter
-                };
-                
-                // Active/Inactive filter
-                let active_status = mod_statuses[*i].1;
-                let status_matches = if app_data.filter_active_only {
-                    active_status
-                } else if app_data.filter_inactive_only {
-                    !active_status
-                } else {
-                    true
-                };
-                
-                // Selected filter
-                let selected_matches = if app_data.filter_selected_only {
-                    m.selected
-                } else {
-                    true
-                };
+/// Keyboard shortcuts for the mods table, ignored while a text field has focus. They act on
+/// the rows as currently filtered and sorted.
+fn mods_table_keys(ui: &egui::Ui, app_data: &mut App) {
+    if ui.ctx().wants_keyboard_input() {
+        return;
+    }
+    let (select_all, up, down, home, end, space, enter, shift) = ui.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::COMMAND, egui::Key::A),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Home),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::End),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Space),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.modifiers.shift,
+        )
+    });
 
-                text_matches && status_matches && selected_matches && filename_matches && fullpath_matches && modtype_matches
-            }).map(|(_, m)| m);
+    if select_all {
+        for &i in &app_data.filtered_mods {
+            app_data.staged_mods[i].selected = true;
+        }
+        app_data.needs_filter = true;
+    }
+    if up {
+        app_data.move_cursor(-1, shift);
+    }
+    if down {
+        app_data.move_cursor(1, shift);
+    }
+    if home {
+        app_data.move_cursor(isize::MIN, shift);
+    }
+    if end {
+        app_data.move_cursor(isize::MAX, shift);
+    }
 
-            for staged_mod in filtered_mods {
-                body.row(20.0, |mut row| {                    row.col(|ui| {
-                        ui.checkbox(&mut staged_mod.selected, "");
-                    });
-                    row.col(|ui| {
-                        let active = app_data
-                            .beam_mod_config
-                            .is_mod_active(&staged_mod.mod_name)
-                            .unwrap();
-                        let text = if active {
-                            RichText::new("Active").color(egui::Color32::from_rgb(50, 200, 50))
-                        } else {
-                            RichText::new("Inactive").color(egui::Color32::from_rgb(200, 50, 50))
-                        };
-                        if ui.button(text).clicked() {
-                            app_data
-                                .beam_mod_config
-                                .set_mod_active(&staged_mod.mod_name, !active)
-                                .unwrap();
-                            app_data
-                                .beam_mod_config
-                                .save_to_path(&app_data.beam_paths.mods_dir)
-                                .unwrap();
-                        }
-                    });
-                    row.col(|ui| {
-                        ui.add(egui::Label::new(&staged_mod.mod_name).wrap_mode(egui::TextWrapMode::Truncate))
-                            .on_hover_text(&staged_mod.mod_name);
-                    });
-                    row.col(|ui| {
-                        let fpath = staged_mod.fullpath.as_deref().unwrap_or("");
-                        ui.add(egui::Label::new(fpath).wrap_mode(egui::TextWrapMode::Truncate))
-                            .on_hover_text(fpath);
-                    });
-                    row.col(|ui| {
-                        let mtype = staged_mod.mod_type.as_deref().unwrap_or("");
-                        ui.add(egui::Label::new(mtype).wrap_mode(egui::TextWrapMode::Truncate))
-                            .on_hover_text(mtype);
-                    });
+    let cursor_mod = app_data
+        .cursor
+        .as_deref()
+        .and_then(|name| app_data.row_of(name))
+        .map(|row| app_data.filtered_mods[row]);
+    if let Some(i) = cursor_mod {
+        if space {
+            app_data.toggle_active(i);
+        }
+        if enter {
+            app_data.details_mod = Some(app_data.staged_mods[i].mod_name.clone());
+        }
+    }
+}
+
+/// Everything known about the mod in `App::details_mod`.
+pub fn mod_details_window(ctx: &egui::Context, app_data: &mut App) {
+    let Some(mod_name) = app_data.details_mod.clone() else {
+        return;
+    };
+    let Some(staged_mod) = app_data.staged_mods.iter().find(|m| m.mod_name == mod_name) else {
+        app_data.details_mod = None;
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new(staged_mod.title.as_deref().unwrap_or(&mod_name))
+        .id(egui::Id::new("mod_details"))
+        .open(&mut open)
+        .resizable(true)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("mod_details_grid")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Mod Name").strong());
+                    ui.label(&staged_mod.mod_name);
+                    ui.end_row();
+                    ui.label(RichText::new("Status").strong());
+                    ui.label(if staged_mod.active { "Active" } else { "Inactive" });
+                    ui.end_row();
+                    for column in [
+                        ModColumn::Author,
+                        ModColumn::Version,
+                        ModColumn::Type,
+                        ModColumn::Filename,
+                        ModColumn::Fullpath,
+                        ModColumn::DateAdded,
+                        ModColumn::Size,
+                    ] {
+                        ui.label(RichText::new(column.label()).strong());
+                        ui.label(column_text(staged_mod, column));
+                        ui.end_row();
+                    }
+                    ui.label(RichText::new("Presets").strong());
+                    ui.label(app_data.presets_of(&mod_name).join(", "));
+                    ui.end_row();
+                    if let Some(presets) = app_data.required_by.get(&mod_name) {
+                        ui.label(RichText::new("Required By").strong());
+                        ui.label(presets.join(", "));
+                        ui.end_row();
+                    }
                 });
+        });
+    if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+        app_data.details_mod = None;
+    }
+}
+
+/// One small colored tag per preset. Returns the preset whose chip was clicked.
+fn preset_chips(ui: &mut egui::Ui, preset_names: &[String]) -> Option<String> {
+    let mut clicked = None;
+    ui.spacing_mut().item_spacing.x = 3.0;
+    for name in preset_names {
+        let response = egui::Frame::none()
+            .fill(chip_color(name))
+            .rounding(6.0)
+            .inner_margin(egui::Margin::symmetric(5.0, 1.0))
+            .show(ui, |ui| {
+                ui.add(
+                    egui::Label::new(RichText::new(name).small().color(egui::Color32::WHITE))
+                        .selectable(false)
+                        .sense(egui::Sense::click()),
+                )
+            })
+            .inner;
+        if response
+            .on_hover_text(format!("Show only mods in '{}'", name))
+            .clicked()
+        {
+            clicked = Some(name.clone());
+        }
+    }
+    clicked
+}
+
+/// A stable color per preset name, so a preset's chips look the same in every row.
+fn chip_color(name: &str) -> egui::Color32 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    name.hash(&mut hasher);
+    let hue = (hasher.finish() % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.55, 0.55, 1.0).into()
+}
+
+/// Show/hide checkboxes for each column, with buttons to move it left or right.
+fn columns_menu(ui: &mut egui::Ui, app_data: &mut App) {
+    let entries = app_data.columns.entries().to_vec();
+    let last = entries.len() - 1;
+    for (index, entry) in entries.into_iter().enumerate() {
+        ui.horizontal(|ui| {
+            if ui.add_enabled(index > 0, egui::Button::new("⏶").small()).on_hover_text("Move left").clicked() {
+                app_data.columns.move_entry(index, -1);
+            }
+            if ui.add_enabled(index < last, egui::Button::new("⏷").small()).on_hover_text("Move right").clicked() {
+                app_data.columns.move_entry(index, 1);
+            }
+            let mut visible = entry.visible;
+            let checkbox = ui.add_enabled(
+                entry.column.can_hide(),
+                egui::Checkbox::new(&mut visible, entry.column.label()),
+            );
+            if checkbox.changed() {
+                app_data.columns.set_visible(entry.column, visible);
             }
         });
+    }
+    ui.separator();
+    if ui.button("Reset").clicked() {
+        app_data.columns = ColumnLayout::default();
+        ui.close_menu();
+    }
 }
 
-/// Buttons to select/deselect/enabled/disable mods etc.
-/// Displayed right above the mods table.
-fn mod_actions_component(ui: &mut egui::Ui, app_data: &mut App) {    ui.horizontal(|ui| {
-    if ui.button(RichText::new("Select All").size(12.0)).clicked() {
-            // Select only the mods that are currently visible given the active filters
-            // Build the same predicate used in the table body
-            let query = app_data.mod_search_query.to_lowercase();
-            for staged_mod in &mut app_data.staged_mods {
-                let text_matches = staged_mod.mod_name.to_lowercase().contains(&query);
+/// Plain text for the columns that just show a `StagedMod` field.
+fn column_text(staged_mod: &StagedMod, column: ModColumn) -> String {
+    match column {
+        ModColumn::Filename => staged_mod.filename.clone().unwrap_or_default(),
+        ModColumn::DateAdded => staged_mod.createtime.map(format_date).unwrap_or_default(),
+        ModColumn::Size => staged_mod.size.map(format_size).unwrap_or_default(),
+        ModColumn::Type => staged_mod.mod_type.clone().unwrap_or_default(),
+        ModColumn::Fullpath => staged_mod.fullpath.clone().unwrap_or_default(),
+        ModColumn::Version => staged_mod.version.clone().unwrap_or_default(),
+        ModColumn::Author => staged_mod.author.clone().unwrap_or_default(),
+        ModColumn::Select | ModColumn::Active | ModColumn::Name | ModColumn::Presets => {
+            unreachable!("{:?} has its own cell", column)
+        }
+    }
+}
 
-                let fullpath_matches = if app_data.fullpath_filter.is_empty() {
-                    true
-                } else {
-                    staged_mod
-                        .fullpath
-                        .as_deref()
-                        .unwrap_or("")
-                        .to_lowercase()
-                        .contains(&app_data.fullpath_filter.to_lowercase())
-                };
+fn truncated_label(ui: &mut egui::Ui, text: &str) {
+    ui.add(
+        egui::Label::new(text)
+            .wrap_mode(egui::TextWrapMode::Truncate)
+            .selectable(false),
+    )
+    .on_hover_text(text);
+}
 
-                let modtype_matches = if app_data.mod_type_filter.is_empty() {
-                    true
-                } else {
-                    staged_mod.mod_type.as_deref().unwrap_or("") == app_data.mod_type_filter
-                };
+/// Lay out `text` with the characters matched by any of the fuzzy `words` highlighted.
+fn highlighted_text(ui: &egui::Ui, text: &str, words: &[&str]) -> egui::text::LayoutJob {
+    let mut matched = vec![false; text.chars().count()];
+    for word in words {
+        if let Some(m) = crate::fuzzy::fuzzy_match(word, text) {
+            for i in m.indices {
+                // Lowercasing can change the char count for a few scripts; skip what doesn't line up
+                if let Some(flag) = matched.get_mut(i) {
+                    *flag = true;
+                }
+            }
+        }
+    }
 
-                // Active/Inactive filter
-                let active_status = app_data.beam_mod_config.is_mod_active(&staged_mod.mod_name).unwrap();
-                let status_matches = if app_data.filter_active_only {
-                    active_status
-                } else if app_data.filter_inactive_only {
-                    !active_status
-                } else {
-                    true
-                };
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let normal = egui::TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+    let highlight = egui::TextFormat::simple(font_id, egui::Color32::from_rgb(50, 150, 200));
+
+    // Append contiguous runs of matched/unmatched characters as single sections
+    let mut job = egui::text::LayoutJob::default();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (c, is_match) in text.chars().zip(matched) {
+        if is_match != run_matched && !run.is_empty() {
+            let format = if run_matched { &highlight } else { &normal };
+            job.append(&std::mem::take(&mut run), 0.0, format.clone());
+        }
+        run_matched = is_match;
+        run.push(c);
+    }
+    let format = if run_matched { highlight } else { normal };
+    job.append(&run, 0.0, format);
+    job
+}
+
+/// The query search box. Syntax errors are highlighted inline and explained underneath.
+fn search_component(ui: &mut egui::Ui, app_data: &mut App) {
+    let error = app_data.search.error.clone();
+    let error_span = error.as_ref().map(|e| e.span.clone());
+
+    let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+        let font_id = egui::TextStyle::Body.resolve(ui.style());
+        let normal = egui::TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+        let mut highlighted = egui::TextFormat::simple(font_id, egui::Color32::WHITE);
+        highlighted.background = egui::Color32::from_rgb(200, 50, 50);
+
+        let mut job = egui::text::LayoutJob::default();
+        // The span comes from the last parse; ignore it if the text has moved on
+        match &error_span {
+            Some(span)
+                if span.end <= text.len()
+                    && text.is_char_boundary(span.start)
+                    && text.is_char_boundary(span.end) =>
+            {
+                job.append(&text[..span.start], 0.0, normal.clone());
+                job.append(&text[span.clone()], 0.0, highlighted);
+                job.append(&text[span.end..], 0.0, normal);
+            }
+            _ => job.append(text, 0.0, normal),
+        }
+        job.wrap.max_width = wrap_width;
+        ui.fonts(|f| f.layout_job(job))
+    };
 
-                // Selected filter doesn't apply to visibility when selecting all
+    ui.horizontal(|ui| {
+        ui.label("Search mods: ");
+        ui.add(
+            egui::TextEdit::singleline(&mut app_data.filter.search)
+                .hint_text("name, or e.g. type:vehicle -active:yes")
+                .desired_width(350.0)
+                .layouter(&mut layouter),
+        );
+        ui.label("(?)").on_hover_ui(|ui| {
+            ui.label("Words fuzzy match the name, filename, path, title and author, best matches first.");
+            ui.label("Combine terms with AND (default), OR, NOT or -, and group with ( ).");
+            ui.label("Use * and ? as wildcards, and \"quotes\" for values with spaces.");
+            ui.separator();
+            for (field, description) in crate::query::FIELDS {
+                ui.label(format!("{}  {}", field, description));
+            }
+        });
+    });
 
-                if text_matches && fullpath_matches && modtype_matches && status_matches {
-                    staged_mod.selected = true;
+    if let Some(error) = error {
+        ui.label(
+            RichText::new(error.message)
+                .color(egui::Color32::from_rgb(200, 50, 50))
+                .small(),
+        );
+    }
+}
+
+/// A clickable column header showing the sort direction and, with several sort keys, the
+/// priority of this column's key. Right-click offers sorting by fields without a column.
+fn sortable_header(ui: &mut egui::Ui, app_data: &mut App, label: &str, option: SortOption) {
+    let key = app_data
+        .sort_keys
+        .iter()
+        .enumerate()
+        .find(|(_, k)| k.option == option);
+    let text = match key {
+        Some((priority, key)) => {
+            let arrow = if key.ascending { "↑" } else { "↓" };
+            if app_data.sort_keys.len() > 1 {
+                format!("{} {}{}", label, arrow, priority + 1)
+            } else {
+                format!("{} {}", label, arrow)
+            }
+        }
+        None => label.to_owned(),
+    };
+
+    let response = ui
+        .add(
+            egui::Label::new(RichText::new(text).strong())
+                .wrap_mode(egui::TextWrapMode::Truncate)
+                .sense(egui::Sense::click()),
+        )
+        .on_hover_text("Click to sort, shift-click to add as a secondary sort key");
+    if response.clicked() {
+        let add = ui.input(|i| i.modifiers.shift);
+        app_data.sort_by_column(option, add);
+    }
+
+    response.context_menu(|ui| {
+        ui.label("Sort by");
+        for option in SortOption::ALL {
+            if ui.button(option.label()).clicked() {
+                app_data.sort_by_column(option, false);
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        ui.label("Then by");
+        for option in SortOption::ALL {
+            let in_use = app_data.sort_keys.iter().any(|k| k.option == option);
+            if ui.add_enabled(!in_use, egui::Button::new(option.label())).clicked() {
+                app_data.sort_by_column(option, true);
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+/// One control per filterable `StagedMod` field, all feeding `App::filter`.
+fn advanced_filters_component(ui: &mut egui::Ui, app_data: &mut App) {
+    let filter = &mut app_data.filter;
+
+    ui.horizontal(|ui| {
+        ui.label("Filename: ");
+        ui.text_edit_singleline(&mut filter.filename);
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Fullpath: ");
+        ui.text_edit_singleline(&mut filter.fullpath);
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Added: ");
+        date_filter_edit(ui, &mut filter.added_after, "after");
+        ui.label("to");
+        date_filter_edit(ui, &mut filter.added_before, "before");
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Type: ");
+        egui::ComboBox::from_id_source("mod_type_combo")
+            .selected_text(if filter.mod_type.is_empty() { "All" } else { &filter.mod_type })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut filter.mod_type, String::new(), "All");
+                for t in &app_data.available_mod_types {
+                    ui.selectable_value(&mut filter.mod_type, t.clone(), t);
+                }
+            });
+
+        ui.label("Preset: ");
+        egui::ComboBox::from_id_source("preset_filter_combo")
+            .selected_text(filter.preset.label().to_owned())
+            .show_ui(ui, |ui| {
+                for option in [PresetFilter::All, PresetFilter::InAny, PresetFilter::InNone] {
+                    let label = option.label().to_owned();
+                    ui.selectable_value(&mut filter.preset, option, label);
+                }
+                ui.separator();
+                for (name, _) in &app_data.presets {
+                    ui.selectable_value(&mut filter.preset, PresetFilter::In(name.clone()), name);
                 }
+            });
+
+        ui.separator();
+
+        // Filter options
+        if ui.selectable_label(filter.active_only, "Active Only").clicked() {
+            filter.active_only = !filter.active_only;
+            filter.inactive_only = false;
+        }
+        if ui.selectable_label(filter.inactive_only, "Inactive Only").clicked() {
+            filter.inactive_only = !filter.inactive_only;
+            filter.active_only = false;
+        }
+        if ui.selectable_label(filter.selected_only, "Selected Only").clicked() {
+            filter.selected_only = !filter.selected_only;
+        }
+
+        ui.separator();
+
+        if ui.button("Clear Filters").clicked() {
+            *filter = crate::filter::ModFilter::default();
+        }
+    });
+}
+
+/// A `YYYY-MM-DD` text box that turns red while its contents can't be parsed.
+fn date_filter_edit(ui: &mut egui::Ui, text: &mut String, hint: &str) {
+    let invalid = !text.is_empty() && crate::filter::parse_date(text).is_none();
+    let mut edit = egui::TextEdit::singleline(text)
+        .hint_text(hint)
+        .desired_width(90.0);
+    if invalid {
+        edit = edit.text_color(egui::Color32::from_rgb(200, 50, 50));
+    }
+    ui.add(edit)
+        .on_hover_text("Date as YYYY-MM-DD. Leave empty for no limit.");
+}
+
+/// Buttons to select/deselect/enabled/disable mods etc.
+/// Displayed right above the mods table.
+fn mod_actions_component(ui: &mut egui::Ui, app_data: &mut App) {    ui.horizontal(|ui| {
+    if ui.button(RichText::new("Select All").size(12.0)).clicked() {
+            // Select exactly the rows the table shows; both come from `ModFilter::matches`
+            app_data.update_mod_view();
+            for &i in &app_data.filtered_mods {
+                app_data.staged_mods[i].selected = true;
             }
+            app_data.needs_filter = true;
         }
 
     if ui.button(RichText::new("Deselect All").size(12.0)).clicked() {
             for staged_mod in &mut app_data.staged_mods {
                 staged_mod.selected = false;
             }
+            app_data.needs_filter = true;
         }
     });    ui.horizontal(|ui| {
             if ui.button(
@@ -532,18 +2073,7 @@ fn mod_actions_component(ui: &mut egui::Ui, app_data: &mut App) {    ui.horizont
                 .size(12.0)
                 .color(egui::Color32::from_rgb(50, 200, 50)),
         ).clicked() {
-            for staged_mod in &app_data.staged_mods {
-                if staged_mod.selected {
-                    app_data
-                        .beam_mod_config
-                        .set_mod_active(&staged_mod.mod_name, true)
-                        .unwrap();
-                }
-            }
-            app_data
-                .beam_mod_config
-                .save_to_path(&app_data.beam_paths.mods_dir)
-                .unwrap();
+            app_data.request_set_active(&selected_mods(app_data), true);
         }
 
         if ui.button(
@@ -551,49 +2081,182 @@ fn mod_actions_component(ui: &mut egui::Ui, app_data: &mut App) {    ui.horizont
                 .size(12.0)
                 .color(egui::Color32::from_rgb(200, 50, 50)),
         ).clicked() {
-            for staged_mod in &app_data.staged_mods {
-                if staged_mod.selected {
-                    app_data
-                        .beam_mod_config
-                        .set_mod_active(&staged_mod.mod_name, false)
-                        .unwrap();
-                }
-            }
-            app_data
-                .beam_mod_config
-                .apply_presets(&app_data.beam_paths.presets_dir)
-                .unwrap();
-            app_data
-                .beam_mod_config
-                .save_to_path(&app_data.beam_paths.mods_dir)
-                .unwrap();
-        }
-    });    if let Some(preset_name) = &app_data.current_preset {
-        ui.horizontal(|ui| {
-            if ui.button(
-                RichText::new(format!("Add to Preset '{}'", preset_name))
-                    .size(12.0)
-                    .color(egui::Color32::from_rgb(50, 150, 200)),
-            ).clicked() {
-                let preset = &mut app_data
-                    .presets
-                    .iter_mut()
-                    .find(|(name, _)| name == preset_name)
-                    .unwrap()
-                    .1;
-                for staged_mod in &app_data.staged_mods {
-                    if staged_mod.selected {
-                        preset.add_mod(&staged_mod.mod_name);
-                    }
+            app_data.request_set_active(&selected_mods(app_data), false);
+        }
+    });    ui.horizontal(|ui| {
+        let selected = selected_mods(app_data);
+        let color = egui::Color32::from_rgb(50, 150, 200);
+        ui.add_enabled_ui(!selected.is_empty(), |ui| {
+            ui.menu_button(RichText::new("Add Selected to Preset…").size(12.0).color(color), |ui| {
+                if let Some(preset_name) = preset_picker(ui, &app_data.presets, &selected, true) {
+                    app_data.add_mods_to_preset(&preset_name, &selected);
                 }
-                preset
-                    .save_to_path(&app_data.beam_paths.presets_dir)
-                    .unwrap();
-                app_data
-                    .beam_mod_config
-                    .apply_presets(&app_data.beam_paths.presets_dir)
-                    .unwrap();
+            });
+            ui.menu_button(RichText::new("Remove Selected from Preset…").size(12.0).color(color), |ui| {
+                if let Some(preset_name) = preset_picker(ui, &app_data.presets, &selected, false) {
+                    app_data.remove_mods_from_preset(&preset_name, &selected);
+                }
+            });
+        });
+        if ui.button(RichText::new("Membership Matrix").size(12.0)).clicked() {
+            app_data.matrix_open = true;
+        }
+    });
+}
+
+/// Mods (rows) against presets (columns), with a checkbox per membership. Shows the mods
+/// the table currently shows, or only the selected ones.
+pub fn membership_matrix_window(ctx: &egui::Context, app_data: &mut App) {
+    if !app_data.matrix_open {
+        return;
+    }
+    let mut open = true;
+    let mut changes: Vec<(String, String, bool)> = Vec::new();
+    egui::Window::new("Preset Membership")
+        .open(&mut open)
+        .resizable(true)
+        .default_size([600.0, 400.0])
+        .show(ctx, |ui| {
+            ui.checkbox(&mut app_data.matrix_selected_only, "Selected mods only");
+            if app_data.presets.is_empty() {
+                ui.label("There are no presets yet.");
+                return;
+            }
+
+            app_data.update_mod_view();
+            let rows: Vec<usize> = app_data
+                .filtered_mods
+                .iter()
+                .copied()
+                .filter(|&i| !app_data.matrix_selected_only || app_data.staged_mods[i].selected)
+                .collect();
+            let presets = &app_data.presets;
+            let staged_mods = &app_data.staged_mods;
+
+            ui.push_id("membership_matrix", |ui| {
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::initial(200.0).at_least(100.0).resizable(true))
+                    .columns(Column::auto().at_least(60.0), presets.len())
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.label(RichText::new(format!("Mod ({})", rows.len())).strong());
+                        });
+                        for (name, _) in presets {
+                            header.col(|ui| {
+                                ui.label(RichText::new(name).strong());
+                            });
+                        }
+                    })
+                    .body(|body| {
+                        body.rows(20.0, rows.len(), |mut row| {
+                            let mod_name = &staged_mods[rows[row.index()]].mod_name;
+                            row.col(|ui| truncated_label(ui, mod_name));
+                            for (preset_name, preset) in presets {
+                                row.col(|ui| {
+                                    let mut member = preset.get_mods().contains(mod_name);
+                                    if ui.checkbox(&mut member, "").changed() {
+                                        changes.push((preset_name.clone(), mod_name.clone(), member));
+                                    }
+                                });
+                            }
+                        });
+                    });
+            });
+        });
+
+    for (preset_name, mod_name, member) in changes {
+        if member {
+            app_data.add_mods_to_preset(&preset_name, &[mod_name]);
+        } else {
+            app_data.remove_mods_from_preset(&preset_name, &[mod_name]);
+        }
+    }
+    if !open {
+        app_data.matrix_open = false;
+    }
+}
+
+/// Selected mods among the shown rows, in table order. Selected mods the filter hides are
+/// left out, so bulk actions only touch what the table displays.
+fn selected_mods(app_data: &App) -> Vec<String> {
+    app_data
+        .filtered_mods
+        .iter()
+        .map(|&i| &app_data.staged_mods[i])
+        .filter(|m| m.selected)
+        .map(|m| m.mod_name.clone())
+        .collect()
+}
+
+/// Create and edit groups of mods and presets that must never be active together.
+pub fn exclusion_groups_window(ctx: &egui::Context, app_data: &mut App) {
+    if !app_data.exclusion_groups_open {
+        return;
+    }
+    let mut open = true;
+    let mut changed = false;
+    let mut group_to_delete = None;
+    egui::Window::new("Exclusion Groups")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(350.0)
+        .show(ctx, |ui| {
+            ui.label(
+                RichText::new("Enabling one member of a group disables the others. Add mods from their right-click menu.")
+                    .small(),
+            );
+            let presets = &app_data.presets;
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                for (index, group) in app_data.preset_meta.exclusion_groups.iter_mut().enumerate() {
+                    ui.push_id(index, |ui| {
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            changed |= ui.text_edit_singleline(&mut group.name).lost_focus();
+                            if ui.button("Delete").clicked() {
+                                group_to_delete = Some(index);
+                            }
+                        });
+                        let mut member_to_remove = None;
+                        for (member_index, member) in group.members.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("×").clicked() {
+                                    member_to_remove = Some(member_index);
+                                }
+                                ui.label(member.label());
+                            });
+                        }
+                        if let Some(member_index) = member_to_remove {
+                            group.members.remove(member_index);
+                            changed = true;
+                        }
+                        ui.menu_button("Add preset", |ui| {
+                            for (name, _) in presets {
+                                let member = GroupMember::Preset(name.clone());
+                                if !group.members.contains(&member) && ui.button(name).clicked() {
+                                    group.members.push(member);
+                                    changed = true;
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+                }
+            });
+            ui.separator();
+            if ui.button("New Group").clicked() {
+                app_data.add_to_exclusion_group(None, Vec::new());
             }
         });
+    if let Some(index) = group_to_delete {
+        app_data.preset_meta.exclusion_groups.remove(index);
+        changed = true;
+    }
+    if changed {
+        app_data.preset_meta.save(&app_data.beam_paths.preset_meta_dir);
+    }
+    if !open {
+        app_data.exclusion_groups_open = false;
     }
 }
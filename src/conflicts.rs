@@ -0,0 +1,59 @@
+//! Mods that look like the same mod installed more than once.
+//!
+//! Without opening the archives this can only go by db.json metadata: two archives whose names
+//! differ only by a version suffix (`drift_car_v1.zip`, `drift_car_v2.zip`), or two archives
+//! with the same title.
+
+use crate::StagedMod;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictReason {
+    SameBaseName,
+    SameTitle,
+}
+
+impl ConflictReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConflictReason::SameBaseName => "same name apart from version",
+            ConflictReason::SameTitle => "same title",
+        }
+    }
+}
+
+pub struct Conflict<'a> {
+    pub other: &'a StagedMod,
+    pub reason: ConflictReason,
+}
+
+/// Every other mod in `staged_mods` that conflicts with `target`.
+pub fn conflicts_of<'a>(staged_mods: &'a [StagedMod], target: &StagedMod) -> Vec<Conflict<'a>> {
+    let target_base = base_name(&target.mod_name);
+    let target_title = target.title.as_deref().filter(|t| !t.is_empty());
+    staged_mods
+        .iter()
+        .filter(|m| m.mod_name != target.mod_name)
+        .filter_map(|other| {
+            let reason = if !target_base.is_empty() && base_name(&other.mod_name) == target_base {
+                ConflictReason::SameBaseName
+            } else if target_title.is_some_and(|t| other.title.as_deref() == Some(t)) {
+                ConflictReason::SameTitle
+            } else {
+                return None;
+            };
+            Some(Conflict { other, reason })
+        })
+        .collect()
+}
+
+/// A mod name without its extension and trailing version, e.g. `drift_car_v1.2` -> `drift_car`.
+pub fn base_name(mod_name: &str) -> String {
+    let name = mod_name.to_lowercase();
+    let name = name.strip_suffix(".zip").unwrap_or(&name);
+    let name = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    let name = name
+        .strip_suffix("_v")
+        .or_else(|| name.strip_suffix("-v"))
+        .unwrap_or(name);
+    name.trim_end_matches(['_', '-', ' ']).to_owned()
+}
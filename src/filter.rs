@@ -0,0 +1,176 @@
+//! Mod filtering shared by the mods table and the bulk actions above it.
+//!
+//! Everything that decides whether a mod is "visible" lives in `ModFilter::matches`, so the
+//! table and actions like Select All can never disagree about which mods are shown.
+
+use crate::query::{Expr, QueryContext};
+use crate::StagedMod;
+use serde::{Deserialize, Serialize};
+
+/// Which presets a mod has to be in.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PresetFilter {
+    #[default]
+    All,
+    InAny,
+    InNone,
+    In(String),
+}
+
+impl PresetFilter {
+    pub fn label(&self) -> &str {
+        match self {
+            PresetFilter::All => "All",
+            PresetFilter::InAny => "In any preset",
+            PresetFilter::InNone => "In no preset",
+            PresetFilter::In(name) => name,
+        }
+    }
+
+    /// `presets` are the names of the presets containing the mod.
+    fn matches(&self, presets: &[String]) -> bool {
+        match self {
+            PresetFilter::All => true,
+            PresetFilter::InAny => !presets.is_empty(),
+            PresetFilter::InNone => presets.is_empty(),
+            PresetFilter::In(name) => presets.contains(name),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModFilter {
+    /// The main search box, in the `query` language.
+    pub search: String,
+    pub filename: String,
+    pub fullpath: String,
+    /// Exact mod type, empty for all types.
+    pub mod_type: String,
+    /// `YYYY-MM-DD`, inclusive. Ignored while empty or unparsable.
+    pub added_after: String,
+    /// `YYYY-MM-DD`, inclusive. Ignored while empty or unparsable.
+    pub added_before: String,
+    pub active_only: bool,
+    pub inactive_only: bool,
+    pub selected_only: bool,
+    pub preset: PresetFilter,
+}
+
+impl ModFilter {
+    /// Whether a staged mod passes every filter. `search` is the parsed form of `self.search`,
+    /// kept by the caller so it isn't re-parsed for every mod.
+    pub fn matches(
+        &self,
+        staged_mod: &StagedMod,
+        search: Option<&Expr>,
+        ctx: &QueryContext,
+    ) -> bool {
+        // Destructure so that adding a field to `StagedMod` forces a decision about filtering it
+        let StagedMod {
+            mod_name,
+            selected,
+            active,
+            createtime,
+            filename,
+            fullpath,
+            mod_type,
+            size: _,    // Only shown and sorted by
+            title: _,   // Matched through the search query
+            author: _,  // Matched through the search query
+            version: _, // Matched through the search query
+            hash: _,    // Only used to find renamed mods
+        } = staged_mod;
+
+        let status_matches = if self.active_only {
+            *active
+        } else if self.inactive_only {
+            !*active
+        } else {
+            true
+        };
+
+        let presets = ctx
+            .preset_index
+            .get(mod_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        search.is_none_or(|expr| expr.matches(staged_mod, ctx))
+            && contains(filename.as_deref(), &self.filename)
+            && contains(fullpath.as_deref(), &self.fullpath)
+            && (self.mod_type.is_empty() || mod_type.as_deref().unwrap_or("") == self.mod_type)
+            && self.added_matches(*createtime)
+            && status_matches
+            && (!self.selected_only || *selected)
+            && self.preset.matches(presets)
+    }
+
+    fn added_matches(&self, createtime: Option<i64>) -> bool {
+        let after = parse_date(&self.added_after);
+        let before = parse_date(&self.added_before);
+        if after.is_none() && before.is_none() {
+            return true;
+        }
+        // Mods without a creation time can't satisfy a date range
+        let Some(time) = createtime else {
+            return false;
+        };
+        after.is_none_or(|start| time >= start)
+            && before.is_none_or(|end| time < end + SECONDS_PER_DAY)
+    }
+}
+
+/// Case-insensitive substring match where an empty filter matches everything.
+fn contains(value: Option<&str>, filter: &str) -> bool {
+    filter.is_empty()
+        || value
+            .unwrap_or("")
+            .to_lowercase()
+            .contains(&filter.to_lowercase())
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Parse a `YYYY-MM-DD` date into a unix timestamp at midnight UTC.
+pub fn parse_date(text: &str) -> Option<i64> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) * SECONDS_PER_DAY)
+}
+
+/// Format a unix timestamp as a `YYYY-MM-DD` date (UTC).
+pub fn format_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Howard Hinnant's `days_from_civil`, so we don't need a date crate for two fields.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// The inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,118 @@
+//! Synthetic mod library for benchmarking the mods table.
+//!
+//! Run with `BEAMMM_GUI_BENCH_MODS=5000 cargo run --release` to generate a throwaway userfolder
+//! with that many mods under the system temp dir and point the app at it instead of the real
+//! game folder. Average frame times are logged to stderr while the fixture is active.
+
+use crate::BeamPaths;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Environment variable holding the number of synthetic mods to generate.
+pub const BENCH_MODS_VAR: &str = "BEAMMM_GUI_BENCH_MODS";
+
+/// Fake game version used for the fixture userfolder.
+const BENCH_GAME_VERSION: &str = "0.0";
+
+const MOD_TYPES: [&str; 5] = ["vehicle", "terrain", "scenario", "sound", "app"];
+
+/// Number of synthetic mods requested via `BEAMMM_GUI_BENCH_MODS`, if set.
+pub fn bench_mod_count() -> Option<usize> {
+    std::env::var(BENCH_MODS_VAR).ok()?.parse().ok()
+}
+
+/// Create (or overwrite) a fixture userfolder with `count` mods and return paths into it
+/// along with the fake game version.
+pub fn create(count: usize) -> std::io::Result<(BeamPaths, String)> {
+    let root = std::env::temp_dir().join(format!("beammm-gui-bench-{}", count));
+    let beamng_dir = root.join("BeamNG.drive");
+    let mods_dir = beamng_dir.join(BENCH_GAME_VERSION).join("mods");
+    let beammm_dir = root.join("BeamMM");
+    let presets_dir = beammm_dir.join("presets");
+    std::fs::create_dir_all(&mods_dir)?;
+    std::fs::create_dir_all(&presets_dir)?;
+
+    write_db(&mods_dir, count)?;
+
+    Ok((
+        BeamPaths {
+            beamng_dir,
+            mods_dir,
+            preset_meta_dir: beammm_dir.clone(),
+            beammm_dir,
+            presets_dir,
+        },
+        BENCH_GAME_VERSION.to_owned(),
+    ))
+}
+
+/// Write a `db.json` with `count` deterministic mod entries.
+fn write_db(mods_dir: &Path, count: usize) -> std::io::Result<()> {
+    let mods: serde_json::Map<String, serde_json::Value> = (0..count)
+        .map(|i| {
+            let name = format!("bench_mod_{:05}", i);
+            let folder = if i % 3 == 0 { "/mods/repo/" } else { "/mods/" };
+            let entry = serde_json::json!({
+                "active": i % 2 == 0,
+                "filename": format!("{}.zip", name),
+                "fullpath": format!("{}{}.zip", folder, name),
+                "modType": MOD_TYPES[i % MOD_TYPES.len()],
+                "modData": {
+                    "title": format!("Bench Mod {}", i),
+                    "username": format!("author{}", i % 97),
+                    "version_string": format!("1.{}", i % 13),
+                },
+                "hash": format!("{:040x}", i),
+                "stat": {
+                    "createtime": 1_600_000_000 + (i as i64 * 7919) % 100_000_000,
+                    "filesize": 1_000_000 + (i as i64 * 104_729) % 500_000_000,
+                },
+            });
+            (name, entry)
+        })
+        .collect();
+    let db = serde_json::json!({ "mods": mods });
+    std::fs::write(
+        mods_dir.join("db.json"),
+        serde_json::to_string_pretty(&db).unwrap(),
+    )
+}
+
+/// Rolling frame time statistics, reported to stderr once per `REPORT_INTERVAL`.
+pub struct FrameStats {
+    frames: u32,
+    total: Duration,
+    worst: Duration,
+    last_report: Instant,
+}
+
+impl FrameStats {
+    const REPORT_INTERVAL: Duration = Duration::from_secs(2);
+
+    pub fn new() -> Self {
+        Self {
+            frames: 0,
+            total: Duration::ZERO,
+            worst: Duration::ZERO,
+            last_report: Instant::now(),
+        }
+    }
+
+    /// Record how long one `update` call took and report if the interval has elapsed.
+    pub fn record(&mut self, frame_time: Duration, visible_rows: usize) {
+        self.frames += 1;
+        self.total += frame_time;
+        self.worst = self.worst.max(frame_time);
+
+        if self.last_report.elapsed() >= Self::REPORT_INTERVAL {
+            eprintln!(
+                "bench: {} frames, avg {:.2?}, worst {:.2?}, {} rows after filtering",
+                self.frames,
+                self.total / self.frames,
+                self.worst,
+                visible_rows
+            );
+            *self = Self::new();
+        }
+    }
+}
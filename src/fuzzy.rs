@@ -0,0 +1,254 @@
+//! Fuzzy matching for bare words in the search box.
+//!
+//! A needle matches a haystack if its characters appear in order (a subsequence), scored so
+//! that consecutive runs and matches at word starts rank higher. If there is no subsequence
+//! match, a typo-tolerant fallback accepts a substring within a small edit distance.
+
+/// A successful match: higher `score` is more relevant, `indices` are the matched char
+/// positions in the haystack (for highlighting).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    pub indices: Vec<usize>,
+}
+
+const SCORE_MATCH: i32 = 16;
+const BONUS_CONSECUTIVE: i32 = 12;
+const BONUS_WORD_START: i32 = 10;
+const BONUS_FIRST_CHAR: i32 = 8;
+const PENALTY_GAP: i32 = 1;
+/// Typo matches always rank below clean subsequence matches of the same length.
+const PENALTY_TYPO: i32 = 20;
+
+/// Fuzzy match a lowercase `needle` against `haystack`.
+pub fn fuzzy_match(needle: &str, haystack: &str) -> Option<FuzzyMatch> {
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: Vec::new(),
+        });
+    }
+    let haystack: Vec<char> = haystack.to_lowercase().chars().collect();
+    subsequence_match(&needle, &haystack).or_else(|| typo_match(&needle, &haystack))
+}
+
+fn is_word_start(haystack: &[char], i: usize) -> bool {
+    i == 0 || !haystack[i - 1].is_alphanumeric()
+}
+
+/// Best-scoring subsequence alignment, by dynamic programming over (needle, haystack) positions.
+fn subsequence_match(needle: &[char], haystack: &[char]) -> Option<FuzzyMatch> {
+    let (n, m) = (needle.len(), haystack.len());
+    if n > m {
+        return None;
+    }
+    const NONE: i32 = i32::MIN / 2;
+    // score[i][j]: best score with needle[i] matched at haystack[j]; from[i][j]: previous j
+    let mut score = vec![vec![NONE; m]; n];
+    let mut from = vec![vec![usize::MAX; m]; n];
+
+    for j in 0..m {
+        if haystack[j] == needle[0] {
+            let mut s = SCORE_MATCH;
+            if is_word_start(haystack, j) {
+                s += BONUS_WORD_START;
+            }
+            if j == 0 {
+                s += BONUS_FIRST_CHAR;
+            }
+            score[0][j] = s;
+        }
+    }
+
+    for i in 1..n {
+        // Best (score - gap penalty) over all earlier positions, with where it came from
+        let mut best_prev = NONE;
+        let mut best_prev_j = usize::MAX;
+        for j in i..m {
+            // Extend the gap for the running best, then consider ending the previous match at j-1
+            best_prev -= PENALTY_GAP;
+            let candidate = score[i - 1][j - 1];
+            if candidate > best_prev {
+                best_prev = candidate;
+                best_prev_j = j - 1;
+            }
+            if haystack[j] != needle[i] || best_prev <= NONE {
+                continue;
+            }
+
+            let mut s = best_prev + SCORE_MATCH;
+            if is_word_start(haystack, j) {
+                s += BONUS_WORD_START;
+            }
+            let consecutive = score[i - 1][j - 1];
+            if consecutive > NONE && consecutive + BONUS_CONSECUTIVE + SCORE_MATCH > s {
+                score[i][j] = consecutive + BONUS_CONSECUTIVE + SCORE_MATCH;
+                from[i][j] = j - 1;
+            } else {
+                score[i][j] = s;
+                from[i][j] = best_prev_j;
+            }
+        }
+    }
+
+    let (mut j, &best) = score[n - 1]
+        .iter()
+        .enumerate()
+        .max_by_key(|&(_, s)| *s)?;
+    if best <= NONE {
+        return None;
+    }
+
+    let mut indices = vec![0; n];
+    for i in (0..n).rev() {
+        indices[i] = j;
+        j = from[i][j];
+    }
+    Some(FuzzyMatch {
+        score: best,
+        indices,
+    })
+}
+
+/// Approximate substring match (Sellers' algorithm with adjacent transpositions, so "dirft"
+/// finds "drift") allowing a few edits for longer needles.
+fn typo_match(needle: &[char], haystack: &[char]) -> Option<FuzzyMatch> {
+    let n = needle.len();
+    if n < 4 {
+        return None;
+    }
+    let max_edits = n / 4;
+    let m = haystack.len();
+
+    // dist[i][j]: edits to match needle[..i] ending at haystack[..j]; row 0 is free so the
+    // match can start anywhere
+    let mut dist = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = dist[i - 1][j - 1] + usize::from(needle[i - 1] != haystack[j - 1]);
+            dist[i][j] = substitution
+                .min(dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1);
+            if is_transposition(needle, haystack, i, j) {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    let (end, &edits) = dist[n].iter().enumerate().skip(1).min_by_key(|&(_, d)| *d)?;
+    if edits > max_edits {
+        return None;
+    }
+
+    // Walk back to find which haystack characters lined up exactly
+    let mut indices = Vec::new();
+    let (mut i, mut j) = (n, end);
+    while i > 0 && j > 0 {
+        let exact = needle[i - 1] == haystack[j - 1];
+        if is_transposition(needle, haystack, i, j) && dist[i][j] == dist[i - 2][j - 2] + 1 {
+            indices.extend([j - 1, j - 2]);
+            i -= 2;
+            j -= 2;
+        } else if dist[i][j] == dist[i - 1][j - 1] + usize::from(!exact) {
+            if exact {
+                indices.push(j - 1);
+            }
+            i -= 1;
+            j -= 1;
+        } else if dist[i][j] == dist[i - 1][j] + 1 {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    indices.reverse();
+
+    Some(FuzzyMatch {
+        score: indices.len() as i32 * SCORE_MATCH - edits as i32 * PENALTY_TYPO,
+        indices,
+    })
+}
+
+/// Whether needle[i-2..i] is haystack[j-2..j] with the two characters swapped.
+fn is_transposition(needle: &[char], haystack: &[char], i: usize, j: usize) -> bool {
+    i > 1
+        && j > 1
+        && needle[i - 1] != haystack[j - 1]
+        && needle[i - 1] == haystack[j - 2]
+        && needle[i - 2] == haystack[j - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(needle: &str, haystack: &str) -> i32 {
+        fuzzy_match(needle, haystack).unwrap().score
+    }
+
+    #[test]
+    fn matches_subsequences() {
+        assert!(fuzzy_match("dc", "drift_car").is_some());
+        assert!(fuzzy_match("dftcr", "drift_car").is_some());
+        assert!(fuzzy_match("cd", "drift_car").is_none());
+        assert!(fuzzy_match("xyz", "drift_car").is_none());
+    }
+
+    #[test]
+    fn ignores_haystack_case() {
+        assert_eq!(fuzzy_match("drift", "DRIFT").unwrap().indices, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn empty_needle_matches_anything() {
+        assert_eq!(
+            fuzzy_match("", "anything"),
+            Some(FuzzyMatch {
+                score: 0,
+                indices: Vec::new()
+            })
+        );
+    }
+
+    #[test]
+    fn prefix_ranks_above_scattered() {
+        assert!(score("car", "car_pack") > score("car", "cool_arena_race"));
+        assert!(score("car", "car_pack") > score("car", "scar"));
+    }
+
+    #[test]
+    fn word_start_ranks_above_mid_word() {
+        assert!(score("car", "drift_car") > score("car", "driftscar"));
+        assert!(score("dc", "drift_car") > score("dc", "dxxcxx"));
+    }
+
+    #[test]
+    fn consecutive_ranks_above_gaps() {
+        assert!(score("ab", "xab") > score("ab", "xaxb"));
+    }
+
+    #[test]
+    fn indices_point_at_best_alignment() {
+        // The word-start `c` beats the earlier mid-word one
+        assert_eq!(fuzzy_match("car", "scar_car").unwrap().indices, vec![5, 6, 7]);
+        assert_eq!(fuzzy_match("dc", "drift_car").unwrap().indices, vec![0, 6]);
+    }
+
+    #[test]
+    fn indices_are_char_positions() {
+        assert_eq!(fuzzy_match("ab", "éab").unwrap().indices, vec![1, 2]);
+    }
+
+    #[test]
+    fn tolerates_typos_in_longer_needles() {
+        let typo = fuzzy_match("dirft", "drift_car").unwrap();
+        assert!(typo.score < score("drift", "drift_car"));
+        assert!(!typo.indices.is_empty());
+        // Short needles get no typo allowance
+        assert!(fuzzy_match("cra", "car").is_none());
+    }
+}
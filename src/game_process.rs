@@ -0,0 +1,119 @@
+//! Whether BeamNG.drive is running. The game rewrites db.json while it runs, so changes
+//! written then are lost or clash with its own.
+
+use std::path::Path;
+
+/// Executable names of the game, matched case-insensitively. Under Proton/Wine the Windows
+/// executable shows up in the command line of a Linux process.
+const EXECUTABLES: [&str; 3] = ["beamng.drive.x64.exe", "beamng.drive.exe", "beamng.drive.x64"];
+
+/// How long a lock file counts as the game starting up where we can't tell whether it is
+/// still held. Older ones are likely left over from a crash.
+#[cfg(not(windows))]
+const FRESH_LOCK: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Best guess whether the game is running, from the process list where we can read one and
+/// from lock files the game holds in its userfolder. Returns what gave it away.
+pub fn running_signal(beamng_dir: &Path, version_dir: &Path) -> Option<String> {
+    if let Some(executable) = running_process() {
+        return Some(format!("{} is in the process list", executable));
+    }
+    held_lock_file(beamng_dir)
+        .or_else(|| held_lock_file(version_dir))
+        .map(|path| format!("{} is in use", path.display()))
+}
+
+#[cfg(target_os = "linux")]
+fn running_process() -> Option<String> {
+    let entries = std::fs::read_dir("/proc").ok()?;
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_str().is_some_and(|n| n.bytes().all(|b| b.is_ascii_digit())))
+        .find_map(|e| {
+            // Arguments are separated by NULs; the executable may be given with any path
+            let cmdline = std::fs::read(e.path().join("cmdline")).ok()?;
+            cmdline
+                .split(|&b| b == 0)
+                .take(2)
+                .filter_map(|arg| std::str::from_utf8(arg).ok())
+                .find(|arg| is_game_executable(arg))
+                .map(file_name)
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn running_process() -> Option<String> {
+    None
+}
+
+pub fn is_game_executable(arg: &str) -> bool {
+    EXECUTABLES.contains(&file_name(arg).to_lowercase().as_str())
+}
+
+/// The last component of a Windows or Unix path.
+fn file_name(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).to_owned()
+}
+
+/// The first `*.lock` file directly in `dir` that the game looks to be holding.
+fn held_lock_file(dir: &Path) -> Option<std::path::PathBuf> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("lock")))
+        .find(|path| lock_held(path))
+}
+
+/// Windows refuses to share a file the game has open, so stale locks can be told apart.
+#[cfg(windows)]
+fn lock_held(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    match std::fs::OpenOptions::new().read(true).share_mode(0).open(path) {
+        Ok(_) => false,
+        Err(e) => e.raw_os_error() == Some(ERROR_SHARING_VIOLATION),
+    }
+}
+
+/// Elsewhere locks are advisory, so only a recent one counts. The process list covers a
+/// game that has been running longer.
+#[cfg(not(windows))]
+fn lock_held(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < FRESH_LOCK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_game_executables_by_file_name() {
+        assert!(is_game_executable("C:\\BeamNG.drive\\Bin64\\BeamNG.drive.x64.exe"));
+        assert!(is_game_executable("/games/beamng/BinLinux/BeamNG.drive.x64"));
+        assert!(!is_game_executable("/usr/bin/steam"));
+        assert!(!is_game_executable("BeamNG.drive.x64.exe.bak"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn only_fresh_lock_files_count() {
+        let dir = std::env::temp_dir().join(format!("beammm-gui-locks-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        let lock = std::fs::File::create(dir.join("game.lock")).unwrap();
+        let stale = std::time::SystemTime::now() - FRESH_LOCK * 2;
+        lock.set_modified(stale).unwrap();
+        let stale_found = held_lock_file(&dir);
+        lock.set_modified(std::time::SystemTime::now()).unwrap();
+        let fresh_found = held_lock_file(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(stale_found, None);
+        assert_eq!(fresh_found, Some(dir.join("game.lock")));
+    }
+}
@@ -0,0 +1,330 @@
+//! The per-version folders under the BeamNG.drive userfolder, and moving mods between them.
+//!
+//! The game keeps a separate `<version>/mods` folder for every version it has run as, so
+//! after an update the mods of the previous version stay behind in the old folder.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What a version folder contains.
+#[derive(Debug, Clone)]
+pub struct VersionFolder {
+    pub version: String,
+    pub mods_dir: PathBuf,
+    /// Mod archives in the folder, see `archives`.
+    pub archive_count: usize,
+    /// Mods listed in its db.json, and how many of them are active.
+    pub db_mods: usize,
+    pub db_active: usize,
+}
+
+impl VersionFolder {
+    pub fn summary(&self) -> String {
+        format!(
+            "{} archives, {} in db.json ({} active)",
+            self.archive_count, self.db_mods, self.db_active
+        )
+    }
+}
+
+/// Every version folder under `beamng_dir` that has a mods folder, oldest first.
+pub fn list(beamng_dir: &Path) -> Vec<VersionFolder> {
+    let Ok(entries) = std::fs::read_dir(beamng_dir) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<VersionFolder> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let version = entry.file_name().to_str()?.to_owned();
+            // Same rule beammm uses to find the latest version without version.txt
+            version.parse::<f32>().ok()?;
+            let mods_dir = entry.path().join("mods");
+            if !mods_dir.is_dir() {
+                return None;
+            }
+            let (db_mods, db_active) = db_counts(&mods_dir);
+            Some(VersionFolder {
+                archive_count: archives(&mods_dir).len(),
+                version,
+                mods_dir,
+                db_mods,
+                db_active,
+            })
+        })
+        .collect();
+    folders.sort_by_cached_key(|f| version_key(&f.version));
+    folders
+}
+
+/// `0.9` sorts before `0.10`, unlike plain string or float order.
+fn version_key(version: &str) -> Vec<u32> {
+    version.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+/// Mod archives in a mods folder, relative to it. Only `.zip` files directly in the folder
+/// or in `repo/` count; unpacked mods are left alone.
+pub fn archives(mods_dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    for sub in [Path::new(""), Path::new("repo")] {
+        let Ok(entries) = std::fs::read_dir(mods_dir.join(sub)) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")) {
+                found.push(sub.join(entry.file_name()));
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+fn read_db(mods_dir: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(mods_dir.join("db.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn db_counts(mods_dir: &Path) -> (usize, usize) {
+    let Some(db) = read_db(mods_dir) else {
+        return (0, 0);
+    };
+    let Some(mods) = db.get("mods").and_then(|m| m.as_object()) else {
+        return (0, 0);
+    };
+    let active = mods
+        .values()
+        .filter(|m| m.get("active").and_then(|a| a.as_bool()) == Some(true))
+        .count();
+    (mods.len(), active)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferMode {
+    Copy,
+    Move,
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// Archives copied or moved.
+    pub transferred: usize,
+    /// Archives the target folder already had.
+    pub skipped: usize,
+    /// db.json entries whose active state was carried over.
+    pub states: usize,
+    /// What went wrong, one line per archive (or for db.json).
+    pub errors: Vec<String>,
+}
+
+impl MigrationReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "{} transferred, {} already present, {} active states carried over",
+            self.transferred, self.skipped, self.states
+        )
+    }
+}
+
+/// Copy or move the mod archives of `from` into `to`, keeping archives `to` already has.
+/// With `carry_active`, the db.json entries of `from` are merged into `to`'s as well, so mods
+/// keep their active state; the game fills in anything else on its next scan. An archive
+/// that fails is recorded in the report and the rest carry on.
+pub fn migrate(from: &Path, to: &Path, mode: TransferMode, carry_active: bool) -> MigrationReport {
+    let mut report = MigrationReport::default();
+    for archive in archives(from) {
+        let source = from.join(&archive);
+        let target = to.join(&archive);
+        if target.exists() {
+            report.skipped += 1;
+            continue;
+        }
+        match transfer(&source, &target, mode) {
+            Ok(()) => report.transferred += 1,
+            Err(e) => report.errors.push(format!("{}: {}", archive.display(), e)),
+        }
+    }
+
+    if carry_active {
+        match merge_db(from, to) {
+            Ok(states) => report.states = states,
+            Err(e) => report.errors.push(format!("db.json: {}", e)),
+        }
+    }
+    report
+}
+
+fn transfer(source: &Path, target: &Path, mode: TransferMode) -> io::Result<()> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match mode {
+        TransferMode::Copy => {
+            std::fs::copy(source, target)?;
+        }
+        // Renaming fails across file systems, so fall back to copy and delete
+        TransferMode::Move => {
+            if std::fs::rename(source, target).is_err() {
+                std::fs::copy(source, target)?;
+                std::fs::remove_file(source)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merge the db.json entries of `from` into `to` for mods whose archive `to` now has.
+/// Returns how many entries were merged.
+fn merge_db(from: &Path, to: &Path) -> io::Result<usize> {
+    let Some(source) = read_db(from) else {
+        return Ok(0);
+    };
+    let Some(source_mods) = source.get("mods").and_then(|m| m.as_object()) else {
+        return Ok(0);
+    };
+    let mut target = read_db(to).unwrap_or_else(|| serde_json::json!({ "mods": {} }));
+    let Some(target_mods) = target.get_mut("mods").and_then(|m| m.as_object_mut()) else {
+        return Err(io::Error::other("target db.json has no mods object"));
+    };
+    // db.json paths are relative to the version folder, e.g. `/mods/repo/foo.zip`
+    let version_dir = to.parent().unwrap_or(to);
+    let mut merged = 0;
+    for (mod_name, entry) in source_mods {
+        let installed = entry
+            .get("fullpath")
+            .and_then(|p| p.as_str())
+            .is_none_or(|p| version_dir.join(p.trim_start_matches('/')).exists());
+        if !installed {
+            continue;
+        }
+        match target_mods.get_mut(mod_name) {
+            Some(existing) => {
+                if let (Some(existing), Some(active)) = (existing.as_object_mut(), entry.get("active")) {
+                    existing.insert("active".to_owned(), active.clone());
+                }
+            }
+            None => {
+                target_mods.insert(mod_name.clone(), entry.clone());
+            }
+        }
+        merged += 1;
+    }
+    std::fs::write(
+        to.join("db.json"),
+        serde_json::to_string_pretty(&target).unwrap(),
+    )?;
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A throwaway userfolder with a `0.31` and a `0.32` version folder.
+    struct Userfolder(PathBuf);
+
+    impl Userfolder {
+        fn new(test: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("beammm-gui-{}-{}", test, std::process::id()));
+            let _ = std::fs::remove_dir_all(&root);
+            for version in ["0.31", "0.32"] {
+                std::fs::create_dir_all(root.join(version).join("mods").join("repo")).unwrap();
+            }
+            Self(root)
+        }
+
+        fn mods(&self, version: &str) -> PathBuf {
+            self.0.join(version).join("mods")
+        }
+    }
+
+    impl Drop for Userfolder {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write(path: PathBuf, content: &str) {
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn versions_sort_numerically() {
+        let mut versions = vec!["0.10", "0.9", "0.32", "1.0", "0.9.1"];
+        versions.sort_by_key(|v| version_key(v));
+        assert_eq!(versions, ["0.9", "0.9.1", "0.10", "0.32", "1.0"]);
+    }
+
+    #[test]
+    fn lists_version_folders_with_counts() {
+        let folder = Userfolder::new("list");
+        write(folder.mods("0.31").join("a.zip"), "a");
+        write(folder.mods("0.31").join("repo").join("b.ZIP"), "b");
+        write(folder.mods("0.31").join("notes.txt"), "");
+        std::fs::create_dir_all(folder.0.join("settings")).unwrap();
+        let folders = list(&folder.0);
+        let versions: Vec<&str> = folders.iter().map(|f| f.version.as_str()).collect();
+        assert_eq!(versions, ["0.31", "0.32"]);
+        assert_eq!(folders[0].archive_count, 2);
+        assert_eq!(folders[1].archive_count, 0);
+    }
+
+    #[test]
+    fn copy_keeps_the_source_and_existing_targets() {
+        let folder = Userfolder::new("copy");
+        write(folder.mods("0.31").join("a.zip"), "old a");
+        write(folder.mods("0.31").join("repo").join("b.zip"), "b");
+        write(folder.mods("0.32").join("a.zip"), "new a");
+
+        let report = migrate(&folder.mods("0.31"), &folder.mods("0.32"), TransferMode::Copy, false);
+        assert_eq!((report.transferred, report.skipped), (1, 1));
+        assert!(report.errors.is_empty());
+        assert_eq!(std::fs::read_to_string(folder.mods("0.32").join("a.zip")).unwrap(), "new a");
+        assert!(folder.mods("0.32").join("repo").join("b.zip").exists());
+        assert!(folder.mods("0.31").join("repo").join("b.zip").exists());
+    }
+
+    #[test]
+    fn move_removes_only_transferred_archives() {
+        let folder = Userfolder::new("move");
+        write(folder.mods("0.31").join("a.zip"), "old a");
+        write(folder.mods("0.31").join("b.zip"), "b");
+        write(folder.mods("0.32").join("a.zip"), "new a");
+
+        let report = migrate(&folder.mods("0.31"), &folder.mods("0.32"), TransferMode::Move, false);
+        assert_eq!((report.transferred, report.skipped), (1, 1));
+        assert!(!folder.mods("0.31").join("b.zip").exists());
+        assert_eq!(std::fs::read_to_string(folder.mods("0.32").join("b.zip")).unwrap(), "b");
+        // Skipped archives stay where they were
+        assert!(folder.mods("0.31").join("a.zip").exists());
+    }
+
+    #[test]
+    fn carries_active_states_of_installed_mods() {
+        let folder = Userfolder::new("carry");
+        write(folder.mods("0.31").join("a.zip"), "a");
+        write(
+            folder.mods("0.31").join("db.json"),
+            r#"{ "mods": {
+                "a": { "active": false, "fullpath": "/mods/a.zip" },
+                "gone": { "active": true, "fullpath": "/mods/gone.zip" },
+                "kept": { "active": false, "fullpath": "/mods/kept.zip" }
+            } }"#,
+        );
+        write(folder.mods("0.32").join("kept.zip"), "kept");
+        write(
+            folder.mods("0.32").join("db.json"),
+            r#"{ "mods": { "kept": { "active": true, "fullpath": "/mods/kept.zip", "hash": "h" } } }"#,
+        );
+
+        let report = migrate(&folder.mods("0.31"), &folder.mods("0.32"), TransferMode::Copy, true);
+        assert_eq!(report.states, 2);
+        let db: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(folder.mods("0.32").join("db.json")).unwrap()).unwrap();
+        assert_eq!(db["mods"]["a"]["active"], false);
+        assert_eq!(db["mods"]["kept"]["active"], false);
+        // Other fields of existing entries are kept
+        assert_eq!(db["mods"]["kept"]["hash"], "h");
+        assert!(db["mods"].get("gone").is_none());
+    }
+}
@@ -0,0 +1,215 @@
+//! Persistent GUI session state, stored as `gui_config.json` in the BeamMM directory.
+//!
+//! The file carries a `version` number. Older files are migrated step by step on load, so
+//! each migration only has to know about the version right before it.
+
+use crate::columns::ColumnLayout;
+use crate::filter::ModFilter;
+use crate::launch::LaunchSettings;
+use crate::profiles::Profile;
+use crate::{SortKey, SortOption};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Version written by this build.
+pub const CURRENT_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub fn visuals(self) -> egui::Visuals {
+        match self {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => egui::Visuals::light(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// Outer position of the window, if the platform reports one.
+    pub position: Option<[f32; 2]>,
+    /// Inner size of the window.
+    pub size: [f32; 2],
+    pub maximized: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiConfig {
+    pub version: u64,
+    pub window: Option<WindowGeometry>,
+    pub presets_panel_width: Option<f32>,
+    /// Sort keys in priority order.
+    pub sort_keys: Vec<SortKey>,
+    pub filter: ModFilter,
+    pub advanced_filters_open: bool,
+    /// Preset selected under "Edit Preset".
+    pub last_preset: Option<String>,
+    /// Mods table columns in display order, each shown or hidden.
+    pub columns: ColumnLayout,
+    pub theme: Theme,
+    pub profiles: Vec<Profile>,
+    /// Profile loaded at startup; `None` for the folders found automatically.
+    pub active_profile: Option<String>,
+    /// What the Play button does.
+    pub launch: LaunchSettings,
+    /// Why an existing file couldn't be loaded. Such a config is never saved, so the file
+    /// survives for a fix or a newer build.
+    #[serde(skip)]
+    pub load_error: Option<String>,
+}
+
+impl Default for GuiConfig {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            window: None,
+            presets_panel_width: None,
+            sort_keys: vec![SortKey::ascending(SortOption::Name)],
+            filter: ModFilter::default(),
+            advanced_filters_open: false,
+            last_preset: None,
+            columns: ColumnLayout::default(),
+            theme: Theme::default(),
+            profiles: Vec::new(),
+            active_profile: None,
+            launch: LaunchSettings::default(),
+            load_error: None,
+        }
+    }
+}
+
+impl GuiConfig {
+    fn path(beammm_dir: &Path) -> PathBuf {
+        beammm_dir.join("gui_config.json")
+    }
+
+    /// Load and migrate the config, falling back to defaults if it is missing or unreadable.
+    /// An unreadable file is left alone, see `load_error`.
+    pub fn load(beammm_dir: &Path) -> Self {
+        let path = Self::path(beammm_dir);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        let parsed = serde_json::from_str(&content)
+            .map_err(|e| e.to_string())
+            .and_then(migrate)
+            .and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string()));
+        parsed.unwrap_or_else(|e| {
+            let error = format!("Failed to read {}: {}", path.display(), e);
+            eprintln!("{}", error);
+            Self {
+                load_error: Some(error),
+                ..Self::default()
+            }
+        })
+    }
+
+    /// The active profile, if it still exists.
+    pub fn active_profile(&self) -> Option<&Profile> {
+        let name = self.active_profile.as_ref()?;
+        self.profiles.iter().find(|p| &p.name == name)
+    }
+
+    pub fn save(&self, beammm_dir: &Path) {
+        if self.load_error.is_some() {
+            return;
+        }
+        let path = Self::path(beammm_dir);
+        if let Err(e) = std::fs::write(&path, serde_json::to_string_pretty(self).unwrap()) {
+            eprintln!("Failed to write gui config {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Bring a config of any older version up to `CURRENT_VERSION`.
+fn migrate(mut value: serde_json::Value) -> Result<serde_json::Value, String> {
+    // Files written before versioning only held `advanced_filters_open`
+    let mut version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "config version {} is newer than this build supports ({})",
+            version, CURRENT_VERSION
+        ));
+    }
+
+    while version < CURRENT_VERSION {
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| "config is not a JSON object".to_owned())?;
+        match version {
+            // v0 -> v1: same keys, everything else takes its default
+            0 => {}
+            _ => unreachable!("no migration from version {}", version),
+        }
+        version += 1;
+        object.insert("version".to_owned(), version.into());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_str(content: &str) -> Result<GuiConfig, String> {
+        let value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        serde_json::from_value(migrate(value)?).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn migrates_unversioned_file() {
+        let config = load_str(r#"{ "advanced_filters_open": true }"#).unwrap();
+        assert_eq!(config.version, CURRENT_VERSION);
+        assert!(config.advanced_filters_open);
+        assert_eq!(
+            config,
+            GuiConfig {
+                advanced_filters_open: true,
+                ..GuiConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn current_version_round_trips() {
+        let config = GuiConfig {
+            theme: Theme::Light,
+            last_preset: Some("Racing".to_owned()),
+            ..GuiConfig::default()
+        };
+        let saved = serde_json::to_string(&config).unwrap();
+        assert_eq!(load_str(&saved).unwrap(), config);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let error = load_str(r#"{ "version": 99 }"#).unwrap_err();
+        assert!(error.contains("newer"));
+    }
+
+    #[test]
+    fn rejects_non_objects() {
+        assert!(load_str("[]").is_err());
+    }
+
+    #[test]
+    fn unreadable_file_is_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("beammm-gui-config-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = GuiConfig::path(&dir);
+        std::fs::write(&path, r#"{ "version": 99 }"#).unwrap();
+
+        let config = GuiConfig::load(&dir);
+        assert!(config.load_error.is_some());
+        config.save(&dir);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"{ "version": 99 }"#);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
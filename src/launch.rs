@@ -0,0 +1,138 @@
+//! Starting the game from the Play button.
+
+use serde::{Deserialize, Serialize};
+use std::process::{Child, Command};
+
+/// BeamNG.drive's Steam app id, launched through the Steam client.
+const STEAM_URL: &str = "steam://rungameid/284160";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchSettings {
+    /// A URL such as `steam://rungameid/284160`, or a command line: the game executable, or
+    /// e.g. a Proton invocation. Quote arguments containing spaces.
+    pub command: String,
+    /// Preset to apply exclusively before starting; `None` starts with the mods as they are.
+    pub preset: Option<String>,
+    /// Put the previous mod state back once the game exits.
+    pub restore_after_exit: bool,
+}
+
+impl Default for LaunchSettings {
+    fn default() -> Self {
+        Self {
+            command: STEAM_URL.to_owned(),
+            preset: None,
+            restore_after_exit: false,
+        }
+    }
+}
+
+/// Run the launch command. Returns the started process, or `None` when a URL was handed to
+/// another program.
+pub fn start(command: &str) -> Result<Option<Child>, String> {
+    let command = command.trim();
+    if command.contains("://") {
+        return crate::platform::open_url(command)
+            .map(|()| None)
+            .map_err(|e| format!("Failed to open {}: {}", command, e));
+    }
+    let mut words = split_command(command).into_iter();
+    let program = words.next().ok_or_else(|| "No launch command set".to_owned())?;
+    Command::new(&program)
+        .args(words)
+        .spawn()
+        .map(Some)
+        .map_err(|e| format!("Failed to start {}: {}", program, e))
+}
+
+/// Whether the command runs the game executable directly, rather than a wrapper or
+/// launcher that may exit while the game keeps running.
+pub fn starts_game(command: &str) -> bool {
+    split_command(command.trim())
+        .first()
+        .is_some_and(|program| crate::game_process::is_game_executable(program))
+}
+
+/// Split a command line into words at whitespace, keeping "double" or 'single' quoted parts
+/// together.
+fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in command.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            None => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_whitespace() {
+        assert_eq!(split_command("  game   -nosteam\t-gfx vk "), ["game", "-nosteam", "-gfx", "vk"]);
+        assert!(split_command("   ").is_empty());
+    }
+
+    #[test]
+    fn quoted_paths_keep_spaces() {
+        assert_eq!(
+            split_command("\"/games/Beam NG/BinLinux/BeamNG.drive.x64\" -userpath '/home/me/Beam NG'"),
+            ["/games/Beam NG/BinLinux/BeamNG.drive.x64", "-userpath", "/home/me/Beam NG"]
+        );
+    }
+
+    #[test]
+    fn mixed_quotes() {
+        assert_eq!(split_command("\"it's\" 'say \"hi\"'"), ["it's", "say \"hi\""]);
+        // Quoted parts join the word around them
+        assert_eq!(split_command("--path=\"a b\"/c"), ["--path=a b/c"]);
+        assert_eq!(split_command("\"\""), [""]);
+    }
+
+    #[test]
+    fn windows_backslashes_are_kept() {
+        assert_eq!(
+            split_command("\"C:\\Program Files (x86)\\Steam\\BeamNG.drive.exe\" -console"),
+            ["C:\\Program Files (x86)\\Steam\\BeamNG.drive.exe", "-console"]
+        );
+        assert_eq!(split_command("C:\\Games\\beam.exe"), ["C:\\Games\\beam.exe"]);
+    }
+
+    #[test]
+    fn unclosed_quote_runs_to_the_end() {
+        assert_eq!(split_command("run \"a b"), ["run", "a b"]);
+    }
+
+    #[test]
+    fn only_the_game_executable_counts_as_the_game() {
+        assert!(starts_game("\"C:\\Program Files (x86)\\Steam\\BeamNG.drive.x64.exe\" -console"));
+        assert!(starts_game("/games/BinLinux/BeamNG.drive.x64 -gfx vk"));
+        assert!(!starts_game("gamemoderun /games/BinLinux/BeamNG.drive.x64"));
+        assert!(!starts_game("steam -applaunch 284160"));
+        assert!(!starts_game(""));
+    }
+}
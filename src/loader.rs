@@ -0,0 +1,143 @@
+//! Background loading of the mod config, db.json metadata and presets.
+//!
+//! Loading big mod folders can take a while, so it runs on a worker thread that reports
+//! progress back to the UI and can be cancelled between steps.
+
+use crate::App;
+use eframe::egui;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+/// Why loading did not produce an `App`.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The user pressed Cancel.
+    Cancelled,
+    Beammm(beammm::Error),
+    /// The command line arguments can't be used, see `cli::PathOverrides::problem`.
+    Arguments(String),
+}
+
+impl From<beammm::Error> for LoadError {
+    fn from(e: beammm::Error) -> Self {
+        LoadError::Beammm(e)
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        LoadError::Beammm(e.into())
+    }
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Cancelled => write!(f, "Loading was cancelled."),
+            LoadError::Beammm(e) => write!(f, "{}", e),
+            LoadError::Arguments(problem) => write!(f, "{}", problem),
+        }
+    }
+}
+
+enum LoadMessage {
+    Progress {
+        stage: String,
+        done: usize,
+        total: usize,
+    },
+    Finished(Result<Box<App>, LoadError>),
+}
+
+/// Handed to the loading code so it can report what it is doing and notice cancellation.
+pub struct Progress {
+    tx: Sender<LoadMessage>,
+    cancel: Arc<AtomicBool>,
+    ctx: egui::Context,
+}
+
+impl Progress {
+    /// Report the current stage. `total` may be 0 when the amount of work is unknown.
+    pub fn report(&self, stage: &str, done: usize, total: usize) {
+        // The receiver only goes away if the UI dropped the loader, in which case nobody cares
+        let _ = self.tx.send(LoadMessage::Progress {
+            stage: stage.to_owned(),
+            done,
+            total,
+        });
+        self.ctx.request_repaint();
+    }
+
+    /// Bail out with `LoadError::Cancelled` if the user asked to stop.
+    pub fn check_cancelled(&self) -> Result<(), LoadError> {
+        if self.cancel.load(Ordering::Relaxed) {
+            Err(LoadError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The UI side of a running load.
+pub struct Loader {
+    rx: Receiver<LoadMessage>,
+    cancel: Arc<AtomicBool>,
+    pub stage: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Loader {
+    /// Spawn a worker thread running `load` and return a handle to poll it from the UI.
+    pub fn spawn<F>(ctx: &egui::Context, load: F) -> Self
+    where
+        F: FnOnce(&Progress) -> Result<App, LoadError> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let progress = Progress {
+            tx,
+            cancel: cancel.clone(),
+            ctx: ctx.clone(),
+        };
+
+        std::thread::spawn(move || {
+            let result = load(&progress).map(Box::new);
+            let _ = progress.tx.send(LoadMessage::Finished(result));
+            progress.ctx.request_repaint();
+        });
+
+        Self {
+            rx,
+            cancel,
+            stage: "Starting".to_owned(),
+            done: 0,
+            total: 0,
+        }
+    }
+
+    /// Ask the worker to stop at its next checkpoint.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// Drain pending messages, returning the result once the worker has finished.
+    pub fn poll(&mut self) -> Option<Result<Box<App>, LoadError>> {
+        while let Ok(message) = self.rx.try_recv() {
+            match message {
+                LoadMessage::Progress { stage, done, total } => {
+                    self.stage = stage;
+                    self.done = done;
+                    self.total = total;
+                }
+                LoadMessage::Finished(result) => return Some(result),
+            }
+        }
+        None
+    }
+}
@@ -2,20 +2,56 @@
 
 use beammm::Preset;
 use eframe::egui;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+mod cli;
+mod columns;
 mod components;
+mod conflicts;
+mod filter;
+mod fixture;
+mod fuzzy;
+mod game_process;
+mod game_versions;
+mod gui_config;
+mod launch;
+mod loader;
+mod platform;
+mod preset_check;
+mod preset_meta;
+mod preset_plan;
+mod profiles;
+mod query;
+mod views;
 
 fn main() -> eframe::Result {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+    cli::init();
+
+    // Window geometry and theme have to be known before the window exists, so read the
+    // config here as well as in `App::new`
+    let gui_config = cli::overrides()
+        .beammm_dir()
+        .map(|dir| gui_config::GuiConfig::load(&dir))
+        .unwrap_or_default();
+    let mut viewport = egui::ViewportBuilder::default();
+    if let Some(window) = &gui_config.window {
+        viewport = viewport
+            .with_inner_size(window.size)
+            .with_maximized(window.maximized);
+        if let Some(position) = window.position {
+            viewport = viewport.with_position(position);
+        }
+    }
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default(),
+        viewport,
         ..Default::default()
     };
     eframe::run_native(
         "BeamMM.gui",
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             // --- Font setup: try runtime load from `assets/` then fall back to defaults ---
             let mut fonts = egui::FontDefinitions::default();
 
@@ -56,6 +92,7 @@ fn main() -> eframe::Result {
 
             // Reduce vertical spacing slightly to make UI feel less tall
             style.spacing.item_spacing = egui::vec2(8.0, 4.0);
+            style.visuals = gui_config.theme.visuals();
             cc.egui_ctx.set_style(style.clone());
 
             // Log applied text style sizes to help debug font/style issues
@@ -71,7 +108,7 @@ fn main() -> eframe::Result {
                 .unwrap_or(0.0);
             eprintln!("Applied style text sizes: heading={} body={}", heading_size, body_size);
 
-            Ok(Box::<App>::default())
+            Ok(Box::new(Gui::start_loading(&cc.egui_ctx)))
         }),
     )
 }
@@ -82,19 +119,109 @@ struct BeamPaths {
     mods_dir: PathBuf,
     beammm_dir: PathBuf,
     presets_dir: PathBuf,
+    // Where `preset_meta.json` lives: the BeamMM directory, or the active profile's folder
+    preset_meta_dir: PathBuf,
 }
 
 struct StagedMod {
     mod_name: String,
     selected: bool,
+    // Cached from ModCfg; refreshed by `App::refresh_active_statuses` after any mutation
+    active: bool,
     createtime: Option<i64>,
     // Optional metadata from db.json
     filename: Option<String>,
     fullpath: Option<String>,
     mod_type: Option<String>,
+    // Archive size in bytes, from the "stat" block
+    size: Option<u64>,
+    // From the "modData" block, only present for repository mods
+    title: Option<String>,
+    author: Option<String>,
+    version: Option<String>,
+    // Archive hash, used to recognize a mod after it's renamed
+    hash: Option<String>,
+}
+
+impl StagedMod {
+    /// A table row for `mod_name`, with whatever metadata its db.json entry has.
+    fn from_db(mod_name: &str, db: &serde_json::Value, active: bool) -> Self {
+        // Look up the entry under the "mods" object and pull optional fields
+        let entry = db.get("mods").and_then(|mods| mods.get(mod_name));
+
+        let createtime = entry
+            .and_then(|m| m.get("stat"))
+            .and_then(|s| s.get("createtime"))
+            .and_then(|t| t.as_i64());
+
+        let size = entry
+            .and_then(|m| m.get("stat"))
+            .and_then(|s| s.get("filesize"))
+            .and_then(|s| s.as_u64());
+
+        let filename = entry
+            .and_then(|m| m.get("filename"))
+            .and_then(|f| f.as_str())
+            .map(|s| s.to_owned());
+
+        let fullpath = entry
+            .and_then(|m| m.get("fullpath"))
+            .and_then(|f| f.as_str())
+            .map(|s| s.to_owned());
+
+        // Some db.json use "modType" or "modtype" etc — try a few variants
+        let mod_type = entry
+            .and_then(|m| m.get("modType").or_else(|| m.get("modtype")).or_else(|| m.get("type")))
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_owned());
+
+        let mod_data = entry.and_then(|m| m.get("modData"));
+        let title = mod_data
+            .and_then(|d| d.get("title"))
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_owned());
+        let author = mod_data
+            .and_then(|d| d.get("username").or_else(|| d.get("author")))
+            .and_then(|a| a.as_str())
+            .map(|s| s.to_owned());
+        let version = mod_data
+            .and_then(|d| d.get("version_string").or_else(|| d.get("version")))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_owned());
+
+        let hash = entry
+            .and_then(|m| m.get("hash"))
+            .and_then(|h| h.as_str())
+            .map(|s| s.to_owned());
+
+        StagedMod {
+            mod_name: mod_name.to_owned(),
+            selected: false,
+            active,
+            createtime,
+            filename,
+            fullpath,
+            mod_type,
+            size,
+            title,
+            author,
+            version,
+            hash,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// The db.json of a mods folder, or `Null` if it is missing or unreadable.
+fn read_mods_db(mods_dir: &Path) -> serde_json::Value {
+    let db_content = std::fs::read_to_string(mods_dir.join("db.json")).unwrap_or_default();
+    if db_content.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(&db_content).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum SortOption {
     Name,
     Status,
@@ -103,102 +230,319 @@ pub enum SortOption {
     Filename,
     Fullpath,
     ModType,
+    Size,
+    Version,
+    Author,
+}
+
+impl SortOption {
+    pub const ALL: [SortOption; 10] = [
+        SortOption::Name,
+        SortOption::Status,
+        SortOption::Selection,
+        SortOption::Date,
+        SortOption::Filename,
+        SortOption::Fullpath,
+        SortOption::ModType,
+        SortOption::Size,
+        SortOption::Version,
+        SortOption::Author,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortOption::Name => "Name",
+            SortOption::Status => "Status",
+            SortOption::Selection => "Selection",
+            SortOption::Date => "Date Added",
+            SortOption::Filename => "Filename",
+            SortOption::Fullpath => "Fullpath",
+            SortOption::ModType => "Mod Type",
+            SortOption::Size => "Size",
+            SortOption::Version => "Version",
+            SortOption::Author => "Author",
+        }
+    }
+
+    /// Compare two mods by this option alone, ascending.
+    fn compare(self, a: &StagedMod, b: &StagedMod) -> std::cmp::Ordering {
+        match self {
+            SortOption::Name => a.mod_name.cmp(&b.mod_name),
+            SortOption::Status => a.active.cmp(&b.active),
+            SortOption::Selection => a.selected.cmp(&b.selected),
+            SortOption::Date => match (a.createtime, b.createtime) {
+                (Some(a_time), Some(b_time)) => a_time.cmp(&b_time),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
+            SortOption::Filename => {
+                let a_val = a.filename.as_deref().unwrap_or("");
+                let b_val = b.filename.as_deref().unwrap_or("");
+                a_val.cmp(b_val)
+            }
+            SortOption::Fullpath => {
+                let a_val = a.fullpath.as_deref().unwrap_or("");
+                let b_val = b.fullpath.as_deref().unwrap_or("");
+                a_val.cmp(b_val)
+            }
+            SortOption::ModType => {
+                let a_val = a.mod_type.as_deref().unwrap_or("");
+                let b_val = b.mod_type.as_deref().unwrap_or("");
+                a_val.cmp(b_val)
+            }
+            SortOption::Size => a.size.cmp(&b.size),
+            SortOption::Version => {
+                let a_val = a.version.as_deref().unwrap_or("");
+                let b_val = b.version.as_deref().unwrap_or("");
+                a_val.cmp(b_val)
+            }
+            SortOption::Author => {
+                let a_val = a.author.as_deref().unwrap_or("");
+                let b_val = b.author.as_deref().unwrap_or("");
+                a_val.cmp(b_val)
+            }
+        }
+    }
+}
+
+/// One level of a multi-key sort. `App::sort_keys` holds them in priority order.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SortKey {
+    pub option: SortOption,
+    pub ascending: bool,
+}
+
+impl SortKey {
+    pub fn ascending(option: SortOption) -> Self {
+        Self {
+            option,
+            ascending: true,
+        }
+    }
+}
+
+/// Mod state from before Play applied a preset, put back once the game exits.
+struct PlaySession {
+    mod_states: HashMap<String, bool>,
+    enabled_presets: Vec<String>,
+    // The process the launch command started, unless it opened a URL
+    child: Option<std::process::Child>,
+    // Whether `child` is the game itself, so its exit means the game exited
+    child_is_game: bool,
+    // Whether the game has been seen running since
+    seen_running: bool,
+    started: std::time::Instant,
+}
+
+/// Asks `Gui` to load the app again, see `App::reload`.
+struct Reload {
+    // Version folder to load; `None` for the one the game uses
+    version: Option<String>,
 }
 
 struct App {
     beam_mod_config: beammm::game::ModCfg,
     beam_paths: BeamPaths,
+    // Game version whose mods folder is shown
     beamng_version: String,
+    // Version the game itself reports; differs from `beamng_version` when looking at an
+    // older version folder
+    game_version: String,
+    // Every version folder under the userfolder, see `game_versions::list`
+    version_folders: Vec<game_versions::VersionFolder>,
+    // Set to have `Gui` load the app again
+    reload: Option<Reload>,
+    profiles: Vec<profiles::Profile>,
+    active_profile: Option<String>,
+    profiles_open: bool,
+    new_profile: profiles::ProfileDraft,
+    // Why the last "Add Profile" failed, if it did
+    profile_error: Option<String>,
+    migration_open: bool,
+    // Version folder to migrate mods from into `beamng_version`
+    migration_source: Option<String>,
+    migration_carry_active: bool,
+    // Outcome of the last migration, shown in the migration window until it is closed, which
+    // reloads the changed folder
+    migration_result: Option<Result<game_versions::MigrationReport, String>>,
+    // Whether BeamNG.drive looked like it was running at the last check, see `check_game_running`
+    game_running: bool,
+    // What made the last check think the game runs, shown in the banner
+    game_signal: Option<String>,
+    last_game_check: std::time::Instant,
+    // Active states the user changed while the game runs, written to db.json once it exits
+    queued_mod_states: Option<HashMap<String, bool>>,
+    // Active states as last read from or written to db.json, to tell the user's changes
+    // apart from the game's
+    written_mod_states: HashMap<String, bool>,
+    // Write db.json even though the game is running, until it exits
+    write_while_running: bool,
+    // Closing the window was asked for while something would be lost, see `pending_work`
+    quit_confirm_open: bool,
+    quit_confirmed: bool,
+    launch: launch::LaunchSettings,
+    play_session: Option<PlaySession>,
+    // Why the last Play failed, if it did
+    launch_error: Option<String>,
+    // Mods the last enable left off because of an exclusion group, see `note_group_conflicts`
+    group_conflict_notice: Option<String>,
     version: String,
     staged_mods: Vec<StagedMod>,
+    // In priority order, see `PresetMeta::order`
     presets: Vec<(String, Preset)>,
+    preset_meta: preset_meta::PresetMeta,
+    // Mod name -> names of the presets containing it, sorted. Rebuilt from `presets` by
+    // `rebuild_preset_index` whenever membership changes
+    preset_index: HashMap<String, Vec<String>>,
+    // Mod name -> names of the *enabled* presets containing it, i.e. why it's kept active
+    required_by: HashMap<String, Vec<String>>,
+    // Mods of each preset that aren't installed, by preset; see `validate_presets`
+    missing_preset_mods: HashMap<String, Vec<String>>,
     current_preset: Option<String>,
     new_preset_name: String,
-    mod_search_query: String,
-    // Filters for new db.json fields
-    filename_filter: String,
-    fullpath_filter: String,
-    mod_type_filter: String,
+    filter: filter::ModFilter,
+    // Parsed form of `filter.search`
+    search: query::Search,
     available_mod_types: Vec<String>,
-    sort_option: SortOption,
-    sort_ascending: bool,
-    filter_active_only: bool,
-    filter_inactive_only: bool,
-    filter_selected_only: bool,
+    // Primary key first; ties fall through to the next key, then to the mod name
+    sort_keys: Vec<SortKey>,
     needs_sort: bool, // Track if sorting is needed
+    needs_filter: bool, // Track if `filtered_mods` must be rebuilt
+    // Indices into `staged_mods` that pass the current filters, in display order
+    filtered_mods: Vec<usize>,
+    // Keyboard cursor and shift-click anchor in the mods table, by mod name so they survive
+    // re-sorting and re-filtering
+    cursor: Option<String>,
+    selection_anchor: Option<String>,
+    // Scroll the table to the cursor on the next frame, after keyboard navigation
+    scroll_to_cursor: bool,
+    // Mod shown in the details window
+    details_mod: Option<String>,
+    // Mods waiting for the user to confirm uninstalling them
+    pending_uninstall: Vec<String>,
+    // Whether uninstalling also takes the mods out of the presets listing them
+    uninstall_from_presets: bool,
+    // Problems from the last uninstall, shown until dismissed
+    uninstall_report: Vec<String>,
+    // Mods whose conflicts are shown in the conflicts window
+    conflicts_for: Vec<String>,
+    matrix_open: bool,
+    matrix_selected_only: bool,
+    exclusion_groups_open: bool,
+    // Mods waiting for the user to confirm disabling them although enabled presets need them
+    pending_disable: Vec<String>,
+    // Preset toggle waiting for the user to confirm its preview
+    pending_preset_plan: Option<preset_plan::PresetPlan>,
     advanced_filters_open: bool,
+    // Order and visibility of the mods table columns
+    columns: columns::ColumnLayout,
+    theme: gui_config::Theme,
+    presets_panel_width: Option<f32>,
+    window: Option<gui_config::WindowGeometry>,
+    // Why gui_config.json couldn't be read; it isn't written this session if set
+    gui_config_error: Option<String>,
+    // What was last written to gui_config.json, to know when the session state changed
+    saved_gui_config: gui_config::GuiConfig,
+    last_gui_config_save: std::time::Instant,
+    saved_views: views::SavedViews,
+    // Name of the saved view last applied or saved, if any
+    current_view: Option<String>,
+    new_view_name: String,
+    // Only set when running against the benchmark fixture
+    frame_stats: Option<fixture::FrameStats>,
 }
 
-impl Default for App {
-    // We will have to learn how to better handle these possible errors.
-    fn default() -> Self {
-        let beamng_dir = beammm::path::beamng_dir_default().unwrap();
-        let beamng_version = beammm::game_version(&beamng_dir).unwrap();
-        let mods_dir = beammm::path::mods_dir(&beamng_dir, &beamng_version).unwrap();
-        let beammm_dir = beammm::path::beammm_dir().unwrap();
-        let presets_dir = beammm::path::presets_dir(&beammm_dir).unwrap();
+impl App {
+    /// Resolve paths and load everything the UI needs. Runs on the loader's worker thread.
+    /// `version` picks a game version folder other than the one the game currently uses.
+    fn load(progress: &loader::Progress, version: Option<String>) -> Result<Self, loader::LoadError> {
+        if let Some(count) = fixture::bench_mod_count() {
+            progress.report(&format!("Generating {} benchmark mods", count), 0, 0);
+            let (beam_paths, beamng_version) = fixture::create(count)?;
+            let mut app = Self::new(beam_paths, beamng_version, progress)?;
+            app.frame_stats = Some(fixture::FrameStats::new());
+            return Ok(app);
+        }
+
+        progress.report("Locating BeamNG.drive", 0, 0);
+        let overrides = cli::overrides();
+        if let Some(problem) = &overrides.problem {
+            return Err(loader::LoadError::Arguments(problem.clone()));
+        }
+        let beammm_dir = overrides.beammm_dir()?;
+        let profile = gui_config::GuiConfig::load(&beammm_dir).active_profile().cloned();
+        // Command line arguments win over the profile
+        let beamng_dir = match (&overrides.userfolder, &profile) {
+            (Some(dir), _) => dir.clone(),
+            (None, Some(profile)) => profile.beamng_dir.clone(),
+            (None, None) => beammm::path::beamng_dir_default()?,
+        };
+        let game_version = beammm::game_version(&beamng_dir)?;
+        let mods_override = overrides
+            .mods_dir
+            .clone()
+            .or_else(|| profile.as_ref().and_then(|p| p.mods_dir.clone()));
+        // An overridden mods folder stands in for the current version's, not for others
+        let mods_dir = match (mods_override, &version) {
+            (Some(mods_dir), None) => mods_dir,
+            _ => beammm::path::mods_dir(&beamng_dir, version.as_ref().unwrap_or(&game_version))?,
+        };
+        let beamng_version = version.unwrap_or_else(|| game_version.clone());
+        let presets_dir = match profile.as_ref().and_then(|p| p.presets_dir.clone()) {
+            Some(presets_dir) => {
+                std::fs::create_dir_all(&presets_dir)?;
+                presets_dir
+            }
+            None => beammm::path::presets_dir(&beammm_dir)?,
+        };
+        let preset_meta_dir = match &profile {
+            Some(profile) => profiles::data_dir(&beammm_dir, &profile.name, profile.presets_dir.is_none())?,
+            None => beammm_dir.clone(),
+        };
         let beam_paths = BeamPaths {
             beamng_dir: beamng_dir.clone(),
             mods_dir: mods_dir.clone(),
             beammm_dir,
             presets_dir,
+            preset_meta_dir,
         };
-        
-        let mod_cfg = beammm::game::ModCfg::load_from_path(&beam_paths.mods_dir).unwrap();
-        let mut staged_mods = mod_cfg.get_mods().collect::<Vec<&String>>();
-        staged_mods.sort();
+        let mut app = Self::new(beam_paths, beamng_version, progress)?;
+        app.game_version = game_version;
+        Ok(app)
+    }
+
+    /// Load the mod config, db.json metadata and presets from the given paths.
+    fn new(
+        beam_paths: BeamPaths,
+        beamng_version: String,
+        progress: &loader::Progress,
+    ) -> Result<Self, loader::LoadError> {
+        progress.report("Loading mod config", 0, 0);
+        let mod_cfg = beammm::game::ModCfg::load_from_path(&beam_paths.mods_dir)?;
+        let mut mod_names = mod_cfg.get_mods().collect::<Vec<&String>>();
+        mod_names.sort();
+        progress.check_cancelled()?;
 
         // Load db.json to get creation times
-        let db_path = beam_paths.mods_dir.join("db.json");
-        
-        let db_content = match std::fs::read_to_string(&db_path) {
-            Ok(content) => content,
-            Err(_) => String::new()
-        };
-        
-        let db: serde_json::Value = if db_content.is_empty() {
-            serde_json::Value::Null
-        } else {
-            serde_json::from_str(&db_content).unwrap_or(serde_json::Value::Null)
-        };
+        progress.report("Parsing db.json", 0, 0);
+        let db = read_mods_db(&beam_paths.mods_dir);
+        progress.check_cancelled()?;
 
-        let staged_mods: Vec<StagedMod> = staged_mods
-            .into_iter()
-            .map(|mod_name| {
-                // Look up the entry under the "mods" object and pull optional fields
-                let entry = db.get("mods").and_then(|mods| mods.get(&mod_name.clone()));
-
-                let createtime = entry
-                    .and_then(|m| m.get("stat"))
-                    .and_then(|s| s.get("createtime"))
-                    .and_then(|t| t.as_i64());
-
-                let filename = entry
-                    .and_then(|m| m.get("filename"))
-                    .and_then(|f| f.as_str())
-                    .map(|s| s.to_owned());
-
-                let fullpath = entry
-                    .and_then(|m| m.get("fullpath"))
-                    .and_then(|f| f.as_str())
-                    .map(|s| s.to_owned());
-
-                // Some db.json use "modType" or "modtype" etc — try a few variants
-                let mod_type = entry
-                    .and_then(|m| m.get("modType").or_else(|| m.get("modtype")).or_else(|| m.get("type")))
-                    .and_then(|t| t.as_str())
-                    .map(|s| s.to_owned());
-
-                StagedMod {
-                    mod_name: mod_name.to_owned(),
-                    selected: false,
-                    createtime,
-                    filename,
-                    fullpath,
-                    mod_type,
-                }
-            })
-            .collect();
+        let total = mod_names.len();
+        let mut staged_mods = Vec::with_capacity(total);
+        for (i, mod_name) in mod_names.into_iter().enumerate() {
+            // Reporting every mod would flood the channel
+            if i % 256 == 0 {
+                progress.report("Reading mod metadata", i, total);
+                progress.check_cancelled()?;
+            }
+            staged_mods.push(StagedMod::from_db(
+                mod_name,
+                &db,
+                mod_cfg.is_mod_active(mod_name).unwrap_or(false),
+            ));
+        }
         // Compute available mod types before we move staged_mods into the App struct
         let mut available_mod_types: Vec<String> = staged_mods
             .iter()
@@ -207,59 +551,1245 @@ impl Default for App {
             .collect();
         available_mod_types.sort();
         available_mod_types.dedup();
-
-        let presets = Preset::list(&beam_paths.presets_dir)
-            .unwrap()
-            .map(|preset_name| {
-                (
-                    preset_name.clone(),
-                    Preset::load_from_path(&preset_name, &beam_paths.presets_dir).unwrap(),
-                )
-            })
+        let written_mod_states = mod_cfg
+            .get_mods()
+            .map(|m| (m.clone(), mod_cfg.is_mod_active(m).unwrap_or(false)))
             .collect();
-        let advanced_filters_open = false; // Default value for advanced_filters_open
-        Self {
+
+        let preset_names: Vec<String> = Preset::list(&beam_paths.presets_dir)?.collect();
+        let mut presets = Vec::with_capacity(preset_names.len());
+        for (i, preset_name) in preset_names.iter().enumerate() {
+            progress.report("Loading presets", i, preset_names.len());
+            progress.check_cancelled()?;
+            let preset = Preset::load_from_path(preset_name, &beam_paths.presets_dir)?;
+            presets.push((preset_name.clone(), preset));
+        }
+        let preset_meta = preset_meta::PresetMeta::load(&beam_paths.preset_meta_dir);
+        preset_meta.sort_presets(&mut presets);
+        let gui_config = gui_config::GuiConfig::load(&beam_paths.beammm_dir);
+        // Only restore the edited preset if it still exists
+        let current_preset = gui_config
+            .last_preset
+            .clone()
+            .filter(|name| presets.iter().any(|(preset_name, _)| preset_name == name));
+        let saved_views = views::SavedViews::load(&beam_paths.beammm_dir);
+        progress.report("Scanning game versions", 0, 0);
+        let version_folders = game_versions::list(&beam_paths.beamng_dir);
+        let game_signal = game_process::running_signal(
+            &beam_paths.beamng_dir,
+            beam_paths.mods_dir.parent().unwrap_or(&beam_paths.mods_dir),
+        );
+        let mut app = Self {
             beam_mod_config: mod_cfg,
             beam_paths,
+            game_version: beamng_version.clone(),
             beamng_version,
+            version_folders,
+            reload: None,
+            profiles: gui_config.profiles.clone(),
+            active_profile: gui_config.active_profile.clone(),
+            profiles_open: false,
+            new_profile: profiles::ProfileDraft::default(),
+            profile_error: None,
+            migration_open: false,
+            migration_source: None,
+            migration_carry_active: true,
+            migration_result: None,
+            game_running: game_signal.is_some(),
+            game_signal,
+            last_game_check: std::time::Instant::now(),
+            queued_mod_states: None,
+            written_mod_states,
+            write_while_running: false,
+            quit_confirm_open: false,
+            quit_confirmed: false,
+            launch: gui_config.launch.clone(),
+            gui_config_error: gui_config.load_error.clone(),
+            play_session: None,
+            launch_error: None,
+            group_conflict_notice: None,
             version: env!("CARGO_PKG_VERSION").to_owned(),
             staged_mods,
             presets,
-            current_preset: None,
+            preset_meta,
+            preset_index: HashMap::new(),
+            required_by: HashMap::new(),
+            missing_preset_mods: HashMap::new(),
+            current_preset,
             new_preset_name: String::new(),
-            mod_search_query: String::new(),
-            filename_filter: String::new(),
-            fullpath_filter: String::new(),
-            mod_type_filter: String::new(),
+            filter: gui_config.filter.clone(),
+            search: query::Search::default(),
             // Use precomputed available_mod_types
             available_mod_types,
-            sort_option: SortOption::Name,
-            sort_ascending: true,
-            filter_active_only: false,
-            filter_inactive_only: false,
-            filter_selected_only: false,
+            sort_keys: gui_config.sort_keys.clone(),
             needs_sort: true,
-            advanced_filters_open,
+            needs_filter: true,
+            filtered_mods: Vec::new(),
+            cursor: None,
+            selection_anchor: None,
+            scroll_to_cursor: false,
+            details_mod: None,
+            pending_uninstall: Vec::new(),
+            uninstall_from_presets: true,
+            uninstall_report: Vec::new(),
+            conflicts_for: Vec::new(),
+            matrix_open: false,
+            matrix_selected_only: false,
+            exclusion_groups_open: false,
+            pending_disable: Vec::new(),
+            pending_preset_plan: None,
+            advanced_filters_open: gui_config.advanced_filters_open,
+            columns: gui_config.columns.clone(),
+            theme: gui_config.theme,
+            presets_panel_width: gui_config.presets_panel_width,
+            window: gui_config.window.clone(),
+            saved_gui_config: gui_config,
+            last_gui_config_save: std::time::Instant::now(),
+            saved_views,
+            current_view: None,
+            new_view_name: String::new(),
+            frame_stats: None,
+        };
+
+        app.rebuild_preset_index();
+
+        // An explicit default view wins over the filters left over from the last session
+        if let Some(view) = app
+            .saved_views
+            .default_view
+            .as_deref()
+            .and_then(|name| app.saved_views.get(name))
+            .cloned()
+        {
+            app.apply_view(&view);
         }
+        Ok(app)
     }
-}
 
-impl App {
-    fn save_gui_config(&self) {
-        let gui_config_path = self.beam_paths.beammm_dir.join("gui_config.json");
-        let cfg = serde_json::json!({
-            "advanced_filters_open": self.advanced_filters_open,
+    /// Replace the current filter and sort state with a saved view.
+    fn apply_view(&mut self, view: &views::SavedView) {
+        self.filter = view.filter.clone();
+        self.sort_keys = view.sort_keys.clone();
+        self.current_view = Some(view.name.clone());
+        self.needs_sort = true;
+        self.needs_filter = true;
+    }
+
+    /// The current filter and sort state as a view called `name`.
+    fn current_view_state(&self, name: &str) -> views::SavedView {
+        views::SavedView {
+            name: name.to_owned(),
+            filter: self.filter.clone(),
+            sort_keys: self.sort_keys.clone(),
+        }
+    }
+
+    /// Recompute `preset_index` and `required_by`. Call this after anything that changes which
+    /// mods are in which preset, or which presets are enabled.
+    fn rebuild_preset_index(&mut self) {
+        self.preset_index.clear();
+        self.required_by.clear();
+        for (preset_name, preset) in &self.presets {
+            for mod_name in preset.get_mods() {
+                self.preset_index
+                    .entry(mod_name.clone())
+                    .or_default()
+                    .push(preset_name.clone());
+            }
+            // An enabled preset also requires the mods of the presets it includes
+            if preset.is_enabled() {
+                for mod_name in self.preset_meta.resolved_mods(preset_name, &self.presets) {
+                    self.required_by
+                        .entry(mod_name)
+                        .or_default()
+                        .push(preset_name.clone());
+                }
+            }
+        }
+        for preset_names in self
+            .preset_index
+            .values_mut()
+            .chain(self.required_by.values_mut())
+        {
+            preset_names.sort();
+        }
+        // Preset filters and `preset:` search terms depend on membership
+        self.needs_filter = true;
+        self.validate_presets();
+    }
+
+    /// Find the mods of each preset that aren't installed, see `missing_preset_mods`. A preset
+    /// with none missing is recorded as verified with the current game version, and the
+    /// hashes of its mods are remembered to find them again after a rename.
+    fn validate_presets(&mut self) {
+        let installed: HashMap<&str, &StagedMod> = self
+            .staged_mods
+            .iter()
+            .map(|m| (m.mod_name.as_str(), m))
+            .collect();
+        self.missing_preset_mods.clear();
+        let mut meta_changed = false;
+        for (preset_name, preset) in &self.presets {
+            let mut missing = Vec::new();
+            let settings = self.preset_meta.settings_mut(preset_name);
+            for mod_name in preset.get_mods() {
+                match installed.get(mod_name.as_str()) {
+                    Some(staged_mod) => {
+                        if let Some(hash) = &staged_mod.hash {
+                            if settings.hashes.get(mod_name) != Some(hash) {
+                                settings.hashes.insert(mod_name.clone(), hash.clone());
+                                meta_changed = true;
+                            }
+                        }
+                    }
+                    None => missing.push(mod_name.clone()),
+                }
+            }
+            if missing.is_empty() {
+                if settings.verified_version.as_ref() != Some(&self.beamng_version) {
+                    settings.verified_version = Some(self.beamng_version.clone());
+                    meta_changed = true;
+                }
+            } else {
+                self.missing_preset_mods.insert(preset_name.clone(), missing);
+            }
+        }
+        if meta_changed {
+            self.preset_meta.save(&self.beam_paths.preset_meta_dir);
+        }
+    }
+
+    /// Swap a mod of a preset for another, e.g. a missing mod for its renamed archive.
+    fn replace_preset_mod(&mut self, preset_name: &str, old: &str, new: &str) {
+        let Some((_, preset)) = self.presets.iter_mut().find(|(name, _)| name == preset_name) else {
+            return;
+        };
+        preset.remove_mods(&[old.to_owned()]);
+        preset_plan::add_new_mods(preset, &[new.to_owned()]);
+        preset.save_to_path(&self.beam_paths.presets_dir).unwrap();
+        self.preset_meta.settings_mut(preset_name).hashes.remove(old);
+        if preset.is_enabled() {
+            self.apply_enabled_presets();
+            self.save_mod_config();
+            self.refresh_active_statuses();
+        }
+        self.rebuild_preset_index();
+    }
+
+    /// Move the preset at `from` to position `to`, changing its priority, and save the order.
+    fn move_preset(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.presets.len() || to >= self.presets.len() {
+            return;
+        }
+        let preset = self.presets.remove(from);
+        self.presets.insert(to, preset);
+        self.save_preset_order();
+        self.rebuild_preset_index();
+    }
+
+    /// Record the current order of `presets` in preset_meta.json.
+    fn save_preset_order(&mut self) {
+        self.preset_meta.order = self.presets.iter().map(|(name, _)| name.clone()).collect();
+        self.preset_meta.save(&self.beam_paths.preset_meta_dir);
+    }
+
+    /// Position of the highest-priority preset containing `mod_name`, if any. Higher wins.
+    fn mod_priority(&self, mod_name: &str) -> Option<usize> {
+        self.presets_of(mod_name)
+            .iter()
+            .filter_map(|name| self.presets.iter().position(|(n, _)| n == name))
+            .max()
+    }
+
+    /// Names of the presets containing `mod_name`.
+    fn presets_of(&self, mod_name: &str) -> &[String] {
+        self.preset_index
+            .get(mod_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Every mod in the mod config with whether it's active.
+    fn mod_states(&self) -> HashMap<String, bool> {
+        let cfg = &self.beam_mod_config;
+        cfg.get_mods()
+            .map(|m| (m.clone(), cfg.is_mod_active(m).unwrap_or(false)))
+            .collect()
+    }
+
+    /// Write db.json, or hold the active states back while the game is running, see
+    /// `flush_queued_mod_states`.
+    fn save_mod_config(&mut self) {
+        let states = self.mod_states();
+        if self.game_running && !self.write_while_running {
+            // Only what the user changed, so the game's own changes survive the flush
+            let changes: HashMap<String, bool> = states
+                .into_iter()
+                .filter(|(mod_name, active)| self.written_mod_states.get(mod_name) != Some(active))
+                .collect();
+            self.queued_mod_states = (!changes.is_empty()).then_some(changes);
+            return;
+        }
+        self.beam_mod_config
+            .save_to_path(&self.beam_paths.mods_dir)
+            .unwrap();
+        self.written_mod_states = states;
+    }
+
+    /// Write the held back changes. db.json is read again first so whatever the game wrote
+    /// in the meantime (newly found or dropped mods, mods toggled in game) is kept, and the
+    /// table is rebuilt from it.
+    fn flush_queued_mod_states(&mut self) {
+        let Some(changes) = self.queued_mod_states.take() else {
+            return;
+        };
+        let mut cfg = match beammm::game::ModCfg::load_from_path(&self.beam_paths.mods_dir) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                eprintln!("Failed to reload mod config, writing the queued one as is: {}", e);
+                self.beam_mod_config
+                    .save_to_path(&self.beam_paths.mods_dir)
+                    .unwrap();
+                self.written_mod_states = self.mod_states();
+                return;
+            }
+        };
+        for (mod_name, active) in changes {
+            // Mods the game dropped meanwhile are skipped
+            let _ = cfg.set_mod_active(&mod_name, active);
+        }
+        cfg.save_to_path(&self.beam_paths.mods_dir).unwrap();
+        self.beam_mod_config = cfg;
+        self.written_mod_states = self.mod_states();
+        self.reload_staged_mods();
+    }
+
+    /// Rebuild the table rows from the mod config and db.json, keeping the selection.
+    fn reload_staged_mods(&mut self) {
+        let db = read_mods_db(&self.beam_paths.mods_dir);
+        let mut mod_names: Vec<&String> = self.beam_mod_config.get_mods().collect();
+        mod_names.sort();
+        let staged_mods = mod_names
+            .into_iter()
+            .map(|mod_name| {
+                let active = self.beam_mod_config.is_mod_active(mod_name).unwrap_or(false);
+                let mut staged_mod = StagedMod::from_db(mod_name, &db, active);
+                staged_mod.selected = self
+                    .staged_mods
+                    .iter()
+                    .any(|m| m.selected && m.mod_name == *mod_name);
+                staged_mod
+            })
+            .collect();
+        self.staged_mods = staged_mods;
+        self.available_mod_types = self
+            .staged_mods
+            .iter()
+            .filter_map(|m| m.mod_type.clone())
+            .filter(|s| !s.is_empty())
+            .collect();
+        self.available_mod_types.sort();
+        self.available_mod_types.dedup();
+        // Row indices point into the old list until the view is rebuilt
+        self.filtered_mods.clear();
+        self.needs_sort = true;
+        self.rebuild_preset_index();
+    }
+
+    /// Set the active state of mods in the mod config, skipping any it no longer has (e.g.
+    /// the game dropped them while the table still showed them).
+    fn set_config_mods_active(&mut self, mod_names: &[String], active: bool) {
+        for mod_name in mod_names {
+            if self.beam_mod_config.set_mod_active(mod_name, active).is_err() {
+                eprintln!("{} is no longer in the mod config", mod_name);
+            }
+        }
+    }
+
+    /// Look for a running game every couple of seconds. Queued writes go out once it exits.
+    fn check_game_running(&mut self, ctx: &egui::Context) {
+        const INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+        // Keep checking while the window sits idle
+        ctx.request_repaint_after(INTERVAL);
+        if self.last_game_check.elapsed() < INTERVAL {
+            return;
+        }
+        self.last_game_check = std::time::Instant::now();
+        let version_dir = self.beam_paths.mods_dir.parent().unwrap_or(&self.beam_paths.mods_dir);
+        self.game_signal = game_process::running_signal(&self.beam_paths.beamng_dir, version_dir);
+        let running = self.game_signal.is_some();
+        if self.game_running && !running {
+            self.write_while_running = false;
+            self.flush_queued_mod_states();
+        }
+        self.game_running = running;
+        self.watch_play_session();
+    }
+
+    /// Apply the Play preset exclusively, save, and start the game, see `launch::LaunchSettings`.
+    fn play(&mut self) {
+        self.launch_error = None;
+        let previous = PlaySession {
+            mod_states: self.mod_states(),
+            enabled_presets: self
+                .presets
+                .iter()
+                .filter(|(_, p)| p.is_enabled())
+                .map(|(name, _)| name.clone())
+                .collect(),
+            child: None,
+            child_is_game: launch::starts_game(&self.launch.command),
+            seen_running: false,
+            started: std::time::Instant::now(),
+        };
+        let preset_name = self.launch.preset.clone();
+        if let Some(preset_name) = &preset_name {
+            if !self.presets.iter().any(|(name, _)| name == preset_name) {
+                self.launch_error = Some(format!("Preset {} doesn't exist anymore", preset_name));
+                return;
+            }
+            let mut states: HashMap<String, bool> =
+                previous.mod_states.keys().map(|m| (m.clone(), false)).collect();
+            // The preset may list several members of one exclusion group
+            let dropped =
+                preset_plan::apply_preset_rules(&mut states, &self.presets, &self.preset_meta, |name, _| {
+                    name == preset_name
+                });
+            self.note_group_conflicts(&dropped);
+            self.set_enabled_presets(|name| name == preset_name);
+            self.write_mod_states(&states);
+        }
+
+        match launch::start(&self.launch.command) {
+            // Nothing to put back if no preset was applied
+            Ok(child) if self.launch.restore_after_exit && preset_name.is_some() => {
+                self.play_session = Some(PlaySession { child, ..previous });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("{}", e);
+                self.launch_error = Some(e);
+                if preset_name.is_some() {
+                    self.restore_play_session(previous);
+                }
+            }
+        }
+    }
+
+    /// Put the mod state from before Play back once the game has come and gone.
+    fn watch_play_session(&mut self) {
+        // Long enough for Steam to update and start the game
+        const GIVE_UP: std::time::Duration = std::time::Duration::from_secs(300);
+        let running = self.game_running;
+        let Some(session) = &mut self.play_session else {
+            return;
+        };
+        session.seen_running |= running;
+        // Wrappers and launchers exit long before the game, so only the game's own exit counts
+        let child_exited = session
+            .child
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(Some(_))));
+        let game_exited = child_exited && session.child_is_game;
+        if !running && (session.seen_running || game_exited) {
+            if let Some(session) = self.play_session.take() {
+                self.restore_play_session(session);
+            }
+        } else if !session.seen_running && session.started.elapsed() > GIVE_UP {
+            eprintln!("The game never seemed to start; not restoring the previous mods");
+            self.play_session = None;
+        }
+    }
+
+    /// Put the mods from before Play back right away instead of when the game exits, writing
+    /// db.json even if the game is running.
+    fn restore_play_session_now(&mut self) {
+        let Some(session) = self.play_session.take() else {
+            return;
+        };
+        if self.game_running {
+            self.override_write_lock();
+        }
+        self.restore_play_session(session);
+    }
+
+    fn restore_play_session(&mut self, session: PlaySession) {
+        self.set_enabled_presets(|name| session.enabled_presets.iter().any(|n| n == name));
+        self.write_mod_states(&session.mod_states);
+    }
+
+    /// Enable exactly the presets `enabled` returns true for, saving the ones that change.
+    /// Mod states are left to the caller, as disabling turns the preset's mods off.
+    fn set_enabled_presets(&mut self, enabled: impl Fn(&str) -> bool) {
+        for (name, preset) in &mut self.presets {
+            let want = enabled(name);
+            if want == preset.is_enabled() {
+                continue;
+            }
+            if want {
+                preset.enable();
+            } else {
+                preset.force_disable(&mut self.beam_mod_config);
+            }
+            preset.save_to_path(&self.beam_paths.presets_dir).unwrap();
+        }
+    }
+
+    /// Set the given mods' active states and save. Mods no longer installed are skipped.
+    fn write_mod_states(&mut self, states: &HashMap<String, bool>) {
+        for (mod_name, &active) in states {
+            let _ = self.beam_mod_config.set_mod_active(mod_name, active);
+        }
+        self.save_mod_config();
+        self.rebuild_preset_index();
+        self.refresh_active_statuses();
+    }
+
+    /// Write db.json now although the game is running, and keep doing so until it exits.
+    fn override_write_lock(&mut self) {
+        self.write_while_running = true;
+        self.flush_queued_mod_states();
+    }
+
+    /// Re-read the active state of every staged mod from the mod config.
+    /// Call this after anything that mutates `beam_mod_config`.
+    fn refresh_active_statuses(&mut self) {
+        for staged_mod in &mut self.staged_mods {
+            staged_mod.active = self
+                .beam_mod_config
+                .is_mod_active(&staged_mod.mod_name)
+                .unwrap_or(false);
+        }
+        self.needs_filter = true;
+    }
+
+    /// Flip a mod's active state, see `request_set_active`.
+    fn toggle_active(&mut self, i: usize) {
+        let mod_name = self.staged_mods[i].mod_name.clone();
+        let active = self.staged_mods[i].active;
+        self.request_set_active(&[mod_name], !active);
+    }
+
+    /// Enable or disable mods, asking first if an enabled preset requires any of the mods
+    /// being disabled.
+    fn request_set_active(&mut self, mod_names: &[String], active: bool) {
+        if !active && mod_names.iter().any(|m| self.required_by.contains_key(m)) {
+            self.pending_disable = mod_names.to_vec();
+        } else {
+            self.set_mods_active(mod_names, active);
+        }
+    }
+
+    /// Enable or disable mods and save the mod config. Mods disabled here stay off even if an
+    /// enabled preset contains them, until that preset is applied again.
+    fn set_mods_active(&mut self, mod_names: &[String], active: bool) {
+        let mod_names = if active {
+            let (kept, dropped) = self.preset_meta.keep_one_per_group(mod_names);
+            self.note_group_conflicts(&dropped);
+            kept
+        } else {
+            mod_names.to_vec()
+        };
+        self.set_config_mods_active(&mod_names, active);
+        if active {
+            self.enforce_exclusion_groups(&mod_names);
+        }
+        self.save_mod_config();
+        self.refresh_active_statuses();
+    }
+
+    /// Apply the rules of every enabled preset to the mod config, in memory and in priority
+    /// order, see `preset_plan::apply_preset_rules`. Unlike `ModCfg::apply_presets` a preset
+    /// with uninstalled mods still has the rest applied.
+    fn apply_enabled_presets(&mut self) {
+        let mut active = self.mod_states();
+        preset_plan::apply_preset_rules(&mut active, &self.presets, &self.preset_meta, |_, p| {
+            p.is_enabled()
+        });
+        for (mod_name, state) in active {
+            self.beam_mod_config.set_mod_active(&mod_name, state).unwrap();
+        }
+    }
+
+    /// Tell the user which mods were left off because they share an exclusion group with
+    /// another mod being enabled.
+    fn note_group_conflicts(&mut self, dropped: &[String]) {
+        self.group_conflict_notice = (!dropped.is_empty()).then(|| {
+            format!(
+                "Left off, as an exclusion group keeps them from running with another mod being enabled: {}",
+                dropped.join(", ")
+            )
+        });
+    }
+
+    /// After `enabled_mods` were turned on, turn off everything sharing an exclusion group
+    /// with them, including whole presets.
+    fn enforce_exclusion_groups(&mut self, enabled_mods: &[String]) {
+        let (mods_off, mut presets_off) = self.preset_meta.excluded_by(enabled_mods, &[]);
+        // An enabled preset containing an excluded mod would turn it back on the next time
+        // preset rules are applied, so it has to go as well
+        presets_off.extend(preset_plan::enabled_presets_containing(
+            &mods_off,
+            &self.presets,
+            &self.preset_meta,
+        ));
+        let mut presets_changed = false;
+        for (_, preset) in self
+            .presets
+            .iter_mut()
+            .filter(|(name, p)| p.is_enabled() && presets_off.contains(name))
+        {
+            preset.force_disable(&mut self.beam_mod_config);
+            preset.save_to_path(&self.beam_paths.presets_dir).unwrap();
+            presets_changed = true;
+        }
+        if presets_changed {
+            // Mods the disabled presets shared with still enabled ones come back on
+            self.apply_enabled_presets();
+            self.rebuild_preset_index();
+        }
+        for mod_name in &mods_off {
+            let _ = self.beam_mod_config.set_mod_active(mod_name, false);
+        }
+        // What the user just enabled wins over any preset
+        self.set_config_mods_active(enabled_mods, true);
+    }
+
+    /// Add mods to the ones `preset_name` must keep disabled, and re-apply it if enabled.
+    fn add_preset_excludes(&mut self, preset_name: &str, mod_names: &[String]) {
+        let excludes = &mut self.preset_meta.settings_mut(preset_name).excludes;
+        for mod_name in mod_names {
+            if !excludes.contains(mod_name) {
+                excludes.push(mod_name.clone());
+            }
+        }
+        self.preset_rules_changed(preset_name);
+    }
+
+    fn remove_preset_exclude(&mut self, preset_name: &str, mod_name: &str) {
+        self.preset_meta
+            .settings_mut(preset_name)
+            .excludes
+            .retain(|m| m != mod_name);
+        self.preset_rules_changed(preset_name);
+    }
+
+    /// Add members to an exclusion group, or to a new one if `group` is `None`.
+    fn add_to_exclusion_group(&mut self, group: Option<usize>, members: Vec<preset_meta::GroupMember>) {
+        let groups = &mut self.preset_meta.exclusion_groups;
+        let index = group.unwrap_or_else(|| {
+            groups.push(preset_meta::ExclusionGroup {
+                name: format!("Group {}", groups.len() + 1),
+                members: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        let Some(group) = groups.get_mut(index) else {
+            return;
+        };
+        for member in members {
+            if !group.members.contains(&member) {
+                group.members.push(member);
+            }
+        }
+        self.preset_meta.save(&self.beam_paths.preset_meta_dir);
+    }
+
+    /// Save preset metadata after a rule change and apply it right away if the preset is on,
+    /// directly or through an enabled preset including it.
+    fn preset_rules_changed(&mut self, preset_name: &str) {
+        self.preset_meta.save(&self.beam_paths.preset_meta_dir);
+        let in_effect = self.presets.iter().any(|(name, p)| {
+            p.is_enabled()
+                && self
+                    .preset_meta
+                    .resolve(name)
+                    .presets
+                    .iter()
+                    .any(|n| n == preset_name)
         });
-        if let Err(e) = std::fs::write(&gui_config_path, serde_json::to_string_pretty(&cfg).unwrap()) {
-            eprintln!("Failed to write gui config {}: {}", gui_config_path.display(), e);
+        if in_effect {
+            self.apply_enabled_presets();
+            self.save_mod_config();
+            self.refresh_active_statuses();
+        }
+        // Includes change which mods enabled presets require
+        self.rebuild_preset_index();
+    }
+
+    /// Preview toggling a preset; the change is made once the user confirms.
+    fn request_preset_toggle(&mut self, preset_name: &str) {
+        // Mods may have been installed or removed since the last check
+        self.validate_presets();
+        self.pending_preset_plan = preset_plan::PresetPlan::new(
+            preset_name,
+            &self.presets,
+            &self.preset_meta,
+            &self.required_by,
+            &self.staged_mods,
+        );
+    }
+
+    /// Enable or disable a preset as previewed by `plan` and save everything. The mod states
+    /// written are exactly the ones the preview showed.
+    fn apply_preset_plan(&mut self, plan: &preset_plan::PresetPlan) {
+        let was_active: HashMap<&str, bool> = self
+            .staged_mods
+            .iter()
+            .map(|m| (m.mod_name.as_str(), m.active))
+            .collect();
+        for (name, preset) in &mut self.presets {
+            if *name == plan.preset && plan.enable {
+                preset.enable();
+            } else if *name == plan.preset || plan.disable_presets.contains(name) {
+                // Unlike `disable`, this doesn't refuse presets with uninstalled mods. It also
+                // turns their mods off, which is undone below for mods the plan keeps
+                preset.force_disable(&mut self.beam_mod_config);
+                for mod_name in preset.get_mods() {
+                    if let Some(&active) = was_active.get(mod_name.as_str()) {
+                        self.beam_mod_config.set_mod_active(mod_name, active).unwrap();
+                    }
+                }
+            } else {
+                continue;
+            }
+            preset.save_to_path(&self.beam_paths.presets_dir).unwrap();
+        }
+        self.set_config_mods_active(&plan.activate, true);
+        self.set_config_mods_active(&plan.deactivate, false);
+        self.save_mod_config();
+        self.rebuild_preset_index();
+        self.refresh_active_statuses();
+    }
+
+    fn add_mods_to_preset(&mut self, preset_name: &str, mod_names: &[String]) {
+        let Some((_, preset)) = self.presets.iter_mut().find(|(name, _)| name == preset_name) else {
+            return;
+        };
+        preset_plan::add_new_mods(preset, mod_names);
+        preset.save_to_path(&self.beam_paths.presets_dir).unwrap();
+        self.apply_enabled_presets();
+        self.save_mod_config();
+        self.rebuild_preset_index();
+        self.refresh_active_statuses();
+    }
+
+    fn remove_mods_from_preset(&mut self, preset_name: &str, mod_names: &[String]) {
+        let Some((_, preset)) = self.presets.iter_mut().find(|(name, _)| name == preset_name) else {
+            return;
+        };
+        preset.remove_mods(mod_names);
+        preset.save_to_path(&self.beam_paths.presets_dir).unwrap();
+        self.rebuild_preset_index();
+    }
+
+    /// Where a mod's archive lives on disk. db.json paths are relative to the game version
+    /// folder (e.g. `/mods/repo/foo.zip`).
+    fn mod_path(&self, staged_mod: &StagedMod) -> Option<PathBuf> {
+        match (&staged_mod.fullpath, &staged_mod.filename) {
+            (Some(fullpath), _) => Some(
+                self.beam_paths
+                    .mods_dir
+                    .parent()?
+                    .join(fullpath.trim_start_matches('/')),
+            ),
+            (None, Some(filename)) => Some(self.beam_paths.mods_dir.join(filename)),
+            (None, None) => None,
+        }
+    }
+
+    /// The file or folder to delete to uninstall a mod. db.json can hold anything, so the
+    /// path must resolve to something strictly inside the mods folder.
+    fn uninstall_path(&self, staged_mod: &StagedMod) -> Result<PathBuf, String> {
+        let path = self
+            .mod_path(staged_mod)
+            .ok_or_else(|| format!("Don't know where {} is installed", staged_mod.mod_name))?;
+        strictly_inside(&path, &self.beam_paths.mods_dir)
+            .map_err(|e| format!("Not deleting {} for {}: {}", path.display(), staged_mod.mod_name, e))
+    }
+
+    /// What reloading or quitting right now would lose, if anything. Reloads are refused and
+    /// quitting asks first while this is set.
+    fn pending_work(&self) -> Option<&'static str> {
+        if self.play_session.is_some() {
+            return Some("The previous mods are waiting to be restored when BeamNG.drive exits");
+        }
+        if self.queued_mod_states.is_some() {
+            return Some("Mod changes are held back until BeamNG.drive exits");
+        }
+        None
+    }
+
+    /// Load everything again, e.g. for another version folder. Refused while that would lose
+    /// something, see `pending_work`.
+    fn request_reload(&mut self, reload: Reload) -> bool {
+        if let Some(reason) = self.pending_work() {
+            eprintln!("Not reloading: {}", reason);
+            return false;
+        }
+        self.reload = Some(reload);
+        true
+    }
+
+    /// Switch to another profile, or to the automatically found folders for `None`. Takes
+    /// effect by loading everything again.
+    fn switch_profile(&mut self, name: Option<String>) {
+        let previous = std::mem::replace(&mut self.active_profile, name);
+        if !self.request_reload(Reload { version: None }) {
+            self.active_profile = previous;
+        }
+    }
+
+    /// Copy or move the mods of another version folder into the one shown, then reload it.
+    /// The folder is reloaded once the user has seen the result, see `finish_migration`.
+    fn migrate_mods(&mut self, from_version: &str, mode: game_versions::TransferMode) {
+        // Carrying active states writes db.json directly, which the game owns while it runs
+        let blocker = if self.game_running {
+            Some("BeamNG.drive is running")
+        } else {
+            self.pending_work()
+        };
+        if let Some(reason) = blocker {
+            self.migration_result = Some(Err(format!("Not migrating: {}", reason)));
+            return;
+        }
+        let Some(source) = self.version_folders.iter().find(|f| f.version == from_version) else {
+            return;
+        };
+        let report = game_versions::migrate(
+            &source.mods_dir,
+            &self.beam_paths.mods_dir,
+            mode,
+            self.migration_carry_active,
+        );
+        eprintln!("Migrated mods from {}: {}", from_version, report.summary());
+        for error in &report.errors {
+            eprintln!("Failed to migrate {}", error);
+        }
+        self.migration_result = Some(Ok(report));
+    }
+
+    /// Close the migration window, reloading the folder if a migration changed it.
+    fn finish_migration(&mut self) {
+        self.migration_open = false;
+        if let Some(Ok(_)) = self.migration_result.take() {
+            // Even a partial migration changed the folder
+            self.request_reload(Reload {
+                version: Some(self.beamng_version.clone()),
+            });
+        }
+    }
+
+    /// Presets listing any of `mod_names`, sorted.
+    fn presets_containing(&self, mod_names: &[String]) -> Vec<String> {
+        let mut presets: Vec<String> = mod_names
+            .iter()
+            .filter_map(|m| self.preset_index.get(m))
+            .flatten()
+            .cloned()
+            .collect();
+        presets.sort();
+        presets.dedup();
+        presets
+    }
+
+    /// Disable mods, delete their archives (or folders, for unpacked mods) and drop them from
+    /// the table. With `from_presets`, the deleted mods are also taken out of every preset
+    /// listing them; otherwise those presets are left incomplete. The game removes the stale
+    /// db.json entries itself the next time it scans the mods folder.
+    fn uninstall_mods(&mut self, mod_names: &[String], from_presets: bool) {
+        self.set_mods_active(mod_names, false);
+        let mut removed = Vec::new();
+        let mut report = Vec::new();
+        for staged_mod in self.staged_mods.iter().filter(|m| mod_names.contains(&m.mod_name)) {
+            let path = match self.uninstall_path(staged_mod) {
+                Ok(path) => path,
+                Err(e) => {
+                    report.push(e);
+                    continue;
+                }
+            };
+            let result = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            match result {
+                Ok(()) => removed.push(staged_mod.mod_name.clone()),
+                Err(e) => report.push(format!("Failed to delete {}: {}", path.display(), e)),
+            }
+        }
+        self.staged_mods.retain(|m| !removed.contains(&m.mod_name));
+        let affected = self.presets_containing(&removed);
+        if from_presets {
+            for preset_name in &affected {
+                self.remove_mods_from_preset(preset_name, &removed);
+            }
+        } else if !affected.is_empty() {
+            report.push(format!(
+                "These presets still list the uninstalled mods: {}",
+                affected.join(", ")
+            ));
+        }
+        for line in &report {
+            eprintln!("{}", line);
+        }
+        self.uninstall_report = report;
+        // Presets still containing the removed mods are now incomplete
+        self.rebuild_preset_index();
+    }
+
+    /// Position of a mod among the displayed rows, i.e. in `filtered_mods`.
+    fn row_of(&self, mod_name: &str) -> Option<usize> {
+        self.filtered_mods
+            .iter()
+            .position(|&i| self.staged_mods[i].mod_name == mod_name)
+    }
+
+    /// Handle a click on a displayed row. A plain click selects only that mod, ctrl toggles
+    /// it, and shift selects everything from the anchor to it.
+    fn click_row(&mut self, row: usize, modifiers: egui::Modifiers) {
+        let i = self.filtered_mods[row];
+        if modifiers.shift {
+            self.select_range_to(row);
+        } else {
+            if modifiers.command {
+                self.staged_mods[i].selected = !self.staged_mods[i].selected;
+            } else {
+                for staged_mod in &mut self.staged_mods {
+                    staged_mod.selected = false;
+                }
+                self.staged_mods[i].selected = true;
+            }
+            self.selection_anchor = Some(self.staged_mods[i].mod_name.clone());
+        }
+        self.cursor = Some(self.staged_mods[i].mod_name.clone());
+        self.needs_filter = true;
+    }
+
+    /// Select every displayed row between the anchor and `row`, inclusive. Without an anchor
+    /// the cursor is used, or failing that `row` itself becomes the anchor.
+    fn select_range_to(&mut self, row: usize) {
+        let anchor = self
+            .selection_anchor
+            .as_deref()
+            .or(self.cursor.as_deref())
+            .and_then(|name| self.row_of(name))
+            .unwrap_or(row);
+        for &i in &self.filtered_mods[anchor.min(row)..=anchor.max(row)] {
+            self.staged_mods[i].selected = true;
+        }
+        self.selection_anchor = Some(self.staged_mods[self.filtered_mods[anchor]].mod_name.clone());
+        self.needs_filter = true;
+    }
+
+    /// Move the keyboard cursor by `offset` displayed rows, clamped to the table. With
+    /// `extend` the rows passed over are selected, like shift-click.
+    fn move_cursor(&mut self, offset: isize, extend: bool) {
+        let Some(last) = self.filtered_mods.len().checked_sub(1) else {
+            return;
+        };
+        let row = match self.cursor.as_deref().and_then(|name| self.row_of(name)) {
+            Some(row) => row.saturating_add_signed(offset).min(last),
+            // The first key press lands on the first row
+            None => 0,
+        };
+        if extend {
+            self.select_range_to(row);
+        }
+        let mod_name = self.staged_mods[self.filtered_mods[row]].mod_name.clone();
+        if !extend {
+            self.selection_anchor = Some(mod_name.clone());
+        }
+        self.cursor = Some(mod_name);
+        self.scroll_to_cursor = true;
+    }
+
+    /// Whether `filtered_mods` is currently ordered by fuzzy search relevance.
+    fn ranked_by_relevance(&self) -> bool {
+        self.search
+            .expr
+            .as_ref()
+            .is_some_and(|e| !e.fuzzy_words().is_empty())
+    }
+
+    /// Sort `staged_mods` in place by the sort keys.
+    fn sort_staged_mods(&mut self) {
+        let sort_keys = &self.sort_keys;
+        self.staged_mods.sort_by(|a, b| {
+            sort_keys
+                .iter()
+                .map(|key| {
+                    let cmp = key.option.compare(a, b);
+                    if key.ascending {
+                        cmp
+                    } else {
+                        cmp.reverse()
+                    }
+                })
+                .find(|cmp| cmp.is_ne())
+                .unwrap_or_else(|| a.mod_name.cmp(&b.mod_name)) // fallback to name sorting
+        });
+    }
+
+    /// Sort by a column header click. A plain click makes `option` the only key, or flips its
+    /// direction if it already is. With `add` (shift-click) it is appended as a lower-priority
+    /// key instead, and clicking an existing key cycles ascending -> descending -> removed.
+    fn sort_by_column(&mut self, option: SortOption, add: bool) {
+        let existing = self.sort_keys.iter().position(|k| k.option == option);
+        match (add, existing) {
+            (false, Some(0)) if self.sort_keys.len() == 1 => {
+                self.sort_keys[0].ascending = !self.sort_keys[0].ascending;
+            }
+            (false, _) => self.sort_keys = vec![SortKey::ascending(option)],
+            (true, Some(i)) if self.sort_keys[i].ascending => self.sort_keys[i].ascending = false,
+            (true, Some(i)) => {
+                self.sort_keys.remove(i);
+            }
+            (true, None) => self.sort_keys.push(SortKey::ascending(option)),
+        }
+        self.needs_sort = true;
+    }
+
+    /// Re-sort and re-filter only if something invalidated the current view.
+    fn update_mod_view(&mut self) {
+        if self.needs_sort {
+            self.sort_staged_mods();
+            self.needs_sort = false;
+            // Sorting moves mods around, so the cached indices are stale
+            self.needs_filter = true;
+        }
+        if self.search.update(&self.filter.search) {
+            self.needs_filter = true;
+        }
+        if self.needs_filter {
+            let ctx = query::QueryContext {
+                preset_index: &self.preset_index,
+            };
+            let search = self.search.expr.as_ref();
+            self.filtered_mods = (0..self.staged_mods.len())
+                .filter(|&i| self.filter.matches(&self.staged_mods[i], search, &ctx))
+                .collect();
+            // Fuzzy words rank results by relevance; the stable sort keeps the chosen sort
+            // option as the tie-breaker
+            if let Some(expr) = search.filter(|e| !e.fuzzy_words().is_empty()) {
+                let staged_mods = &self.staged_mods;
+                self.filtered_mods
+                    .sort_by_cached_key(|&i| std::cmp::Reverse(expr.relevance(&staged_mods[i])));
+            }
+            self.needs_filter = false;
+        }
+    }
+
+    /// Snapshot of the session state that is persisted between runs.
+    fn gui_config(&self) -> gui_config::GuiConfig {
+        gui_config::GuiConfig {
+            version: gui_config::CURRENT_VERSION,
+            window: self.window.clone(),
+            presets_panel_width: self.presets_panel_width,
+            sort_keys: self.sort_keys.clone(),
+            filter: self.filter.clone(),
+            advanced_filters_open: self.advanced_filters_open,
+            last_preset: self.current_preset.clone(),
+            columns: self.columns.clone(),
+            theme: self.theme,
+            profiles: self.profiles.clone(),
+            active_profile: self.active_profile.clone(),
+            launch: self.launch.clone(),
+            load_error: self.gui_config_error.clone(),
+        }
+    }
+
+    fn save_gui_config(&mut self) {
+        let cfg = self.gui_config();
+        cfg.save(&self.beam_paths.beammm_dir);
+        self.saved_gui_config = cfg;
+        self.last_gui_config_save = std::time::Instant::now();
+    }
+
+    /// Save the session state if it changed, at most once a second so dragging the window
+    /// or typing in the search box doesn't write on every frame.
+    fn autosave_gui_config(&mut self) {
+        if self.last_gui_config_save.elapsed() >= std::time::Duration::from_secs(1)
+            && self.gui_config() != self.saved_gui_config
+        {
+            self.save_gui_config();
+        }
+    }
+
+    /// Remember the window's current position and size.
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (outer, inner, maximized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.outer_rect, viewport.inner_rect, viewport.maximized.unwrap_or(false))
+        });
+        let Some(inner) = inner else {
+            return;
+        };
+        let previous = self.window.take();
+        self.window = Some(if maximized {
+            // Keep the restored geometry so un-maximizing next session returns to it
+            gui_config::WindowGeometry {
+                maximized: true,
+                ..previous.unwrap_or(gui_config::WindowGeometry {
+                    position: None,
+                    size: [inner.width(), inner.height()],
+                    maximized: true,
+                })
+            }
+        } else {
+            gui_config::WindowGeometry {
+                position: outer.map(|r| [r.min.x, r.min.y]),
+                size: [inner.width(), inner.height()],
+                maximized: false,
+            }
+        });
+    }
+}
+
+/// Top-level eframe app. Shows a progress screen until the worker thread hands over an `App`.
+enum Gui {
+    Loading(loader::Loader),
+    Ready(Box<App>),
+    Failed {
+        message: String,
+        // Active profile when loading failed, which may be what's broken
+        profile: Option<String>,
+    },
+}
+
+impl Gui {
+    fn start_loading(ctx: &egui::Context) -> Self {
+        Gui::Loading(loader::Loader::spawn(ctx, |progress| App::load(progress, None)))
+    }
+
+    /// Load again, with the mods folder of `version` or of the version the game uses.
+    fn load_version(ctx: &egui::Context, version: Option<String>) -> Self {
+        Gui::Loading(loader::Loader::spawn(ctx, move |progress| {
+            App::load(progress, version)
+        }))
+    }
+}
+
+impl eframe::App for Gui {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let mut choice = None;
+        match self {
+            Gui::Loading(loader) => {
+                match loader.poll() {
+                    Some(Ok(app)) => *self = Gui::Ready(app),
+                    Some(Err(e)) => {
+                        *self = Gui::Failed {
+                            message: e.to_string(),
+                            profile: cli::overrides()
+                                .beammm_dir()
+                                .ok()
+                                .and_then(|dir| gui_config::GuiConfig::load(&dir).active_profile),
+                        }
+                    }
+                    None => components::loading_screen(ctx, loader),
+                }
+                // Show the first frame of whatever we switched to right away
+                if !matches!(self, Gui::Loading(_)) {
+                    ctx.request_repaint();
+                }
+            }
+            Gui::Ready(app) => {
+                app.update(ctx, frame);
+                if let Some(reload) = app.reload.take() {
+                    app.save_gui_config();
+                    *self = Gui::load_version(ctx, reload.version);
+                }
+            }
+            Gui::Failed { message, profile } => {
+                choice = components::load_failed_screen(ctx, message, profile.as_deref())
+            }
+        }
+        match choice {
+            Some(components::LoadFailedChoice::Retry) => *self = Gui::start_loading(ctx),
+            Some(components::LoadFailedChoice::UseDefaultProfile) => {
+                if let Ok(dir) = cli::overrides().beammm_dir() {
+                    let mut config = gui_config::GuiConfig::load(&dir);
+                    config.active_profile = None;
+                    config.save(&dir);
+                }
+                *self = Gui::start_loading(ctx);
+            }
+            None => {}
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Gui::Ready(app) = self {
+            app.save_gui_config();
+            if let Some(reason) = app.pending_work() {
+                eprintln!("Quit anyway: {}", reason);
+            }
         }
     }
 }
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let frame_start = std::time::Instant::now();
+
+        if ctx.input(|i| i.viewport().close_requested()) && !self.quit_confirmed && self.pending_work().is_some() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.quit_confirm_open = true;
+        }
+        self.check_game_running(ctx);
         components::title_panel(ctx, self);
         components::presets_panel(ctx, self);
         components::mods_panel(ctx, self);
+        components::mod_details_window(ctx, self);
+        components::conflicts_window(ctx, self);
+        components::membership_matrix_window(ctx, self);
+        components::uninstall_confirm_window(ctx, self);
+        components::preset_confirm_window(ctx, self);
+        components::disable_required_window(ctx, self);
+        components::exclusion_groups_window(ctx, self);
+        components::migration_window(ctx, self);
+        components::profiles_window(ctx, self);
+        components::quit_confirm_window(ctx, self);
+
+        self.track_window_geometry(ctx);
+        self.autosave_gui_config();
+
+        if let Some(stats) = &mut self.frame_stats {
+            stats.record(frame_start.elapsed(), self.filtered_mods.len());
+        }
+    }
+}
+
+/// `path` with symlinks and `..` resolved, if that is inside `dir` and not `dir` itself.
+fn strictly_inside(path: &Path, dir: &Path) -> Result<PathBuf, String> {
+    let resolved = path.canonicalize().map_err(|e| e.to_string())?;
+    let dir = dir.canonicalize().map_err(|e| e.to_string())?;
+    if resolved == dir || !resolved.starts_with(&dir) {
+        return Err("it isn't inside the mods folder".to_owned());
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_paths_strictly_inside_a_folder_pass() {
+        let root = std::env::temp_dir().join(format!("beammm-gui-inside-test-{}", std::process::id()));
+        let mods_dir = root.join("0.32").join("mods");
+        std::fs::create_dir_all(mods_dir.join("unpacked").join("car")).unwrap();
+        std::fs::write(mods_dir.join("car.zip"), "").unwrap();
+
+        assert!(strictly_inside(&mods_dir.join("car.zip"), &mods_dir).is_ok());
+        assert!(strictly_inside(&mods_dir.join("unpacked/car"), &mods_dir).is_ok());
+        // An empty filename or fullpath resolves to the folders themselves
+        assert!(strictly_inside(&mods_dir.join(""), &mods_dir).is_err());
+        assert!(strictly_inside(&mods_dir.join("unpacked/.."), &mods_dir).is_err());
+        assert!(strictly_inside(&root.join("0.32"), &mods_dir).is_err());
+        assert!(strictly_inside(&mods_dir.join("../../0.32"), &mods_dir).is_err());
+        assert!(strictly_inside(&mods_dir.join("missing.zip"), &mods_dir).is_err());
+        std::fs::remove_dir_all(&root).unwrap();
     }
 }
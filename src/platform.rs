@@ -0,0 +1,34 @@
+//! Talking to the operating system outside of the game folder.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Open the system file manager with `path` selected, or at least its folder open.
+pub fn reveal_in_file_manager(path: &Path) {
+    #[cfg(target_os = "windows")]
+    let result = Command::new("explorer").arg("/select,").arg(path).spawn();
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg("-R").arg(path).spawn();
+    // There's no common "select this file" across Linux file managers
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = Command::new("xdg-open")
+        .arg(path.parent().unwrap_or(path))
+        .spawn();
+
+    if let Err(e) = result {
+        eprintln!("Failed to open file manager for {}: {}", path.display(), e);
+    }
+}
+
+/// Hand a URL (e.g. `steam://...`) to whatever the system has registered for it.
+pub fn open_url(url: &str) -> std::io::Result<()> {
+    // `start` treats its first quoted argument as a window title, hence the empty one
+    #[cfg(target_os = "windows")]
+    let result = Command::new("cmd").args(["/C", "start", ""]).arg(url).spawn();
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg(url).spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = Command::new("xdg-open").arg(url).spawn();
+
+    result.map(|_| ())
+}
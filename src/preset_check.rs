@@ -0,0 +1,69 @@
+//! Checks presets against the installed mods and suggests replacements for mods that are
+//! gone, e.g. because an update renamed the archive.
+
+use crate::conflicts::base_name;
+use crate::fuzzy::fuzzy_match;
+use crate::StagedMod;
+
+/// How many replacement candidates to offer for one missing mod.
+const MAX_CANDIDATES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchReason {
+    /// Same archive hash as the missing mod had when its preset was last verified.
+    SameHash,
+    SameBaseName,
+    SimilarName,
+}
+
+impl MatchReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchReason::SameHash => "same file",
+            MatchReason::SameBaseName => "same name apart from version",
+            MatchReason::SimilarName => "similar name",
+        }
+    }
+}
+
+pub struct Replacement {
+    pub mod_name: String,
+    pub reason: MatchReason,
+}
+
+/// Installed mods that could stand in for `missing`, best first. `known_hash` is the hash
+/// recorded for it when it was still installed, if any. Mods in `preset_mods` are skipped,
+/// since the preset already has them.
+pub fn find_replacements(
+    missing: &str,
+    known_hash: Option<&str>,
+    preset_mods: &[String],
+    staged_mods: &[StagedMod],
+) -> Vec<Replacement> {
+    let missing_base = base_name(missing);
+    let mut scored: Vec<(i32, Replacement)> = staged_mods
+        .iter()
+        .filter(|staged_mod| !preset_mods.contains(&staged_mod.mod_name))
+        .filter_map(|staged_mod| {
+            let (score, reason) = if known_hash.is_some() && staged_mod.hash.as_deref() == known_hash {
+                (i32::MAX, MatchReason::SameHash)
+            } else if !missing_base.is_empty() && base_name(&staged_mod.mod_name) == missing_base {
+                (i32::MAX - 1, MatchReason::SameBaseName)
+            } else {
+                let score = fuzzy_match(&missing_base, &base_name(&staged_mod.mod_name))?.score;
+                (score, MatchReason::SimilarName)
+            };
+            let replacement = Replacement {
+                mod_name: staged_mod.mod_name.clone(),
+                reason,
+            };
+            Some((score, replacement))
+        })
+        .collect();
+    scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+    scored
+        .into_iter()
+        .take(MAX_CANDIDATES)
+        .map(|(_, replacement)| replacement)
+        .collect()
+}
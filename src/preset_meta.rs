@@ -0,0 +1,338 @@
+//! Preset settings that beammm's preset files have no room for, stored as `preset_meta.json`
+//! in the BeamMM directory, or in the active profile's folder (see `profiles::data_dir`).
+
+use beammm::Preset;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetMeta {
+    /// Preset names in display order. Presets are applied top to bottom, so when two
+    /// disagree the one further down wins.
+    pub order: Vec<String>,
+    /// Per-preset settings, by preset name.
+    pub presets: BTreeMap<String, PresetSettings>,
+    pub exclusion_groups: Vec<ExclusionGroup>,
+    /// Why an existing file couldn't be loaded. The metadata is then never saved, so the
+    /// user's order, rules and groups survive until the file is fixed.
+    #[serde(skip)]
+    pub load_error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetSettings {
+    /// Mods that must be disabled while this preset is enabled.
+    pub excludes: Vec<String>,
+    /// Other presets whose mods and rules this one includes, recursively.
+    pub includes: Vec<String>,
+    /// Game version the preset last had all of its mods installed with.
+    pub verified_version: Option<String>,
+    /// Archive hashes of the preset's mods from when they were installed, used to find them
+    /// again if they are renamed.
+    pub hashes: BTreeMap<String, String>,
+}
+
+/// A preset with everything it includes, see `PresetMeta::resolve`.
+#[derive(Debug, Default)]
+pub struct Resolution {
+    /// The preset and every preset it includes, directly or not. Included presets come
+    /// before the ones including them, ending with the preset itself.
+    pub presets: Vec<String>,
+    /// The first include cycle found, as a path from a preset back to itself.
+    pub cycle: Option<Vec<String>>,
+}
+
+/// Mods and presets that must never be active together. Enabling one member disables the
+/// others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExclusionGroup {
+    pub name: String,
+    pub members: Vec<GroupMember>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GroupMember {
+    Mod(String),
+    Preset(String),
+}
+
+impl GroupMember {
+    pub fn label(&self) -> String {
+        match self {
+            GroupMember::Mod(name) => name.clone(),
+            GroupMember::Preset(name) => format!("preset {}", name),
+        }
+    }
+}
+
+impl PresetMeta {
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join("preset_meta.json")
+    }
+
+    /// Load preset metadata, falling back to none if the file is missing or unreadable.
+    pub fn load(dir: &Path) -> Self {
+        let path = Self::path(dir);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            let error = format!("Failed to read {}: {}", path.display(), e);
+            eprintln!("{}", error);
+            Self {
+                load_error: Some(error),
+                ..Self::default()
+            }
+        })
+    }
+
+    pub fn save(&self, dir: &Path) {
+        if self.load_error.is_some() {
+            return;
+        }
+        let path = Self::path(dir);
+        if let Err(e) = std::fs::write(&path, serde_json::to_string_pretty(self).unwrap()) {
+            eprintln!("Failed to write preset metadata {}: {}", path.display(), e);
+        }
+    }
+
+    /// Mods that `preset_name` must keep disabled.
+    pub fn excludes(&self, preset_name: &str) -> &[String] {
+        self.presets
+            .get(preset_name)
+            .map(|s| s.excludes.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn settings(&self, preset_name: &str) -> Option<&PresetSettings> {
+        self.presets.get(preset_name)
+    }
+
+    /// Presets `preset_name` directly includes.
+    pub fn includes(&self, preset_name: &str) -> &[String] {
+        self.presets
+            .get(preset_name)
+            .map(|s| s.includes.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Follow the includes of `preset_name`. Each preset is visited once, so cycles are
+    /// reported rather than followed forever.
+    pub fn resolve(&self, preset_name: &str) -> Resolution {
+        let mut resolution = Resolution::default();
+        let mut path = Vec::new();
+        self.resolve_into(preset_name, &mut path, &mut resolution);
+        resolution
+    }
+
+    fn resolve_into(&self, preset_name: &str, path: &mut Vec<String>, resolution: &mut Resolution) {
+        if let Some(start) = path.iter().position(|n| n == preset_name) {
+            if resolution.cycle.is_none() {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(preset_name.to_owned());
+                resolution.cycle = Some(cycle);
+            }
+            return;
+        }
+        if resolution.presets.iter().any(|n| n == preset_name) {
+            return;
+        }
+        path.push(preset_name.to_owned());
+        for included in self.includes(preset_name) {
+            self.resolve_into(included, path, resolution);
+        }
+        path.pop();
+        resolution.presets.push(preset_name.to_owned());
+    }
+
+    /// Mods of `preset_name` and of every preset it includes, without duplicates.
+    pub fn resolved_mods(&self, preset_name: &str, presets: &[(String, Preset)]) -> Vec<String> {
+        let mut mods: Vec<String> = Vec::new();
+        for name in self.resolve(preset_name).presets {
+            let Some((_, preset)) = presets.iter().find(|(n, _)| *n == name) else {
+                continue;
+            };
+            for mod_name in preset.get_mods() {
+                if !mods.contains(mod_name) {
+                    mods.push(mod_name.clone());
+                }
+            }
+        }
+        mods
+    }
+
+    /// Whether making `preset_name` include `other` would create a cycle.
+    pub fn would_cycle(&self, preset_name: &str, other: &str) -> bool {
+        self.resolve(other).presets.iter().any(|n| n == preset_name)
+    }
+
+    pub fn settings_mut(&mut self, preset_name: &str) -> &mut PresetSettings {
+        self.presets.entry(preset_name.to_owned()).or_default()
+    }
+
+    /// Everything sharing an exclusion group with one of the given mods or presets, split
+    /// into (mods, presets). The given members themselves are left out.
+    /// Split `mods` into the ones that can be active together and the ones that can't:
+    /// a mod sharing an exclusion group with an earlier one in the list is dropped.
+    pub fn keep_one_per_group(&self, mods: &[String]) -> (Vec<String>, Vec<String>) {
+        let mut kept: Vec<String> = Vec::new();
+        let mut dropped = Vec::new();
+        for mod_name in mods {
+            let member = GroupMember::Mod(mod_name.clone());
+            let clashes = self.exclusion_groups.iter().any(|group| {
+                group.members.contains(&member)
+                    && kept
+                        .iter()
+                        .any(|k| *k != *mod_name && group.members.contains(&GroupMember::Mod(k.clone())))
+            });
+            if clashes {
+                dropped.push(mod_name.clone());
+            } else if !kept.contains(mod_name) {
+                kept.push(mod_name.clone());
+            }
+        }
+        (kept, dropped)
+    }
+
+    pub fn excluded_by(&self, mods: &[String], presets: &[String]) -> (Vec<String>, Vec<String>) {
+        let is_given = |member: &GroupMember| match member {
+            GroupMember::Mod(name) => mods.contains(name),
+            GroupMember::Preset(name) => presets.contains(name),
+        };
+        let mut excluded_mods = Vec::new();
+        let mut excluded_presets = Vec::new();
+        for group in self.exclusion_groups.iter().filter(|g| g.members.iter().any(is_given)) {
+            for member in group.members.iter().filter(|m| !is_given(m)) {
+                let (list, name) = match member {
+                    GroupMember::Mod(name) => (&mut excluded_mods, name),
+                    GroupMember::Preset(name) => (&mut excluded_presets, name),
+                };
+                if !list.contains(name) {
+                    list.push(name.clone());
+                }
+            }
+        }
+        (excluded_mods, excluded_presets)
+    }
+
+    /// Drop everything referring to a deleted preset.
+    pub fn forget_preset(&mut self, preset_name: &str) {
+        self.order.retain(|name| name != preset_name);
+        self.presets.remove(preset_name);
+        for settings in self.presets.values_mut() {
+            settings.includes.retain(|name| name != preset_name);
+        }
+        for group in &mut self.exclusion_groups {
+            group
+                .members
+                .retain(|m| *m != GroupMember::Preset(preset_name.to_owned()));
+        }
+    }
+
+    /// Sort presets into the saved order. Presets that aren't in it yet go last, by name.
+    pub fn sort_presets(&self, presets: &mut [(String, Preset)]) {
+        presets.sort_by_cached_key(|(name, _)| {
+            let position = self.order.iter().position(|n| n == name);
+            (position.unwrap_or(usize::MAX), name.clone())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(includes: &[(&str, &[&str])]) -> PresetMeta {
+        let mut meta = PresetMeta::default();
+        for (preset, included) in includes {
+            meta.settings_mut(preset).includes = included.iter().map(|n| n.to_string()).collect();
+        }
+        meta
+    }
+
+    #[test]
+    fn includes_come_before_includer() {
+        let meta = meta(&[("a", &["b", "c"]), ("b", &["d"])]);
+        let resolution = meta.resolve("a");
+        assert_eq!(resolution.presets, ["d", "b", "c", "a"]);
+        assert_eq!(resolution.cycle, None);
+    }
+
+    #[test]
+    fn preset_without_includes_resolves_to_itself() {
+        assert_eq!(PresetMeta::default().resolve("a").presets, ["a"]);
+    }
+
+    #[test]
+    fn diamond_visits_shared_include_once() {
+        let meta = meta(&[("a", &["b", "c"]), ("b", &["d"]), ("c", &["d"])]);
+        let resolution = meta.resolve("a");
+        assert_eq!(resolution.presets, ["d", "b", "c", "a"]);
+        assert_eq!(resolution.cycle, None);
+    }
+
+    #[test]
+    fn direct_cycle_is_reported() {
+        let meta = meta(&[("a", &["b"]), ("b", &["a"])]);
+        let resolution = meta.resolve("a");
+        assert_eq!(resolution.cycle, Some(vec!["a".into(), "b".into(), "a".into()]));
+        assert_eq!(resolution.presets, ["b", "a"]);
+    }
+
+    #[test]
+    fn indirect_cycle_is_reported() {
+        let meta = meta(&[("a", &["b"]), ("b", &["c"]), ("c", &["b"])]);
+        let resolution = meta.resolve("a");
+        assert_eq!(resolution.cycle, Some(vec!["b".into(), "c".into(), "b".into()]));
+        assert_eq!(resolution.presets, ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn self_include_is_a_cycle() {
+        let meta = meta(&[("a", &["a"])]);
+        assert_eq!(meta.resolve("a").cycle, Some(vec!["a".into(), "a".into()]));
+    }
+
+    #[test]
+    fn unreadable_file_is_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("beammm-gui-preset-meta-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = PresetMeta::path(&dir);
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let mut meta = PresetMeta::load(&dir);
+        assert!(meta.load_error.is_some());
+        meta.settings_mut("racing").verified_version = Some("0.32".to_owned());
+        meta.save(&dir);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ not json");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_first_mod_of_each_group() {
+        let mut meta = PresetMeta::default();
+        for (name, members) in [("tires", ["a", "b"]), ("engines", ["c", "d"])] {
+            meta.exclusion_groups.push(ExclusionGroup {
+                name: name.to_owned(),
+                members: members.iter().map(|m| GroupMember::Mod(m.to_string())).collect(),
+            });
+        }
+        let mods: Vec<String> = ["b", "x", "a", "d", "c"].iter().map(|m| m.to_string()).collect();
+        let (kept, dropped) = meta.keep_one_per_group(&mods);
+        assert_eq!(kept, ["b", "x", "d"]);
+        assert_eq!(dropped, ["a", "c"]);
+    }
+
+    #[test]
+    fn would_cycle_checks_the_whole_chain() {
+        let meta = meta(&[("a", &["b"]), ("b", &["c"])]);
+        assert!(meta.would_cycle("c", "a"));
+        assert!(meta.would_cycle("b", "a"));
+        assert!(meta.would_cycle("a", "a"));
+        assert!(!meta.would_cycle("a", "c"));
+        assert!(!meta.would_cycle("d", "a"));
+    }
+}
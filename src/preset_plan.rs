@@ -0,0 +1,416 @@
+//! What enabling or disabling a preset will change, worked out before anything is written.
+
+use crate::preset_meta::PresetMeta;
+use crate::StagedMod;
+use beammm::Preset;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct PresetPlan {
+    pub preset: String,
+    /// True to enable the preset, false to disable it.
+    pub enable: bool,
+    /// Mods that will go from inactive to active.
+    pub activate: Vec<String>,
+    /// Mods that will go from active to inactive.
+    pub deactivate: Vec<String>,
+    /// Other enabled presets that an exclusion group turns off along with this change, as
+    /// members or because they contain a mod it excludes.
+    pub disable_presets: Vec<String>,
+    /// Mods of this preset that other enabled presets also contain, with those presets.
+    pub shared: Vec<(String, Vec<String>)>,
+    /// Mods of this preset that aren't installed, so can't be toggled.
+    pub missing: Vec<String>,
+    /// An include cycle that keeps the preset from being enabled, if any.
+    pub cycle: Option<Vec<String>>,
+}
+
+impl PresetPlan {
+    /// Simulate toggling `preset_name`. Disabling turns the preset's mods, including those of
+    /// presets it includes, off; enabling also disables whatever shares an exclusion group
+    /// with it. Then the rules of every enabled preset are applied in priority order, see
+    /// `apply_preset_rules`.
+    pub fn new(
+        preset_name: &str,
+        presets: &[(String, Preset)],
+        meta: &PresetMeta,
+        required_by: &HashMap<String, Vec<String>>,
+        staged_mods: &[StagedMod],
+    ) -> Option<Self> {
+        let (_, preset) = presets.iter().find(|(name, _)| name == preset_name)?;
+        let enable = !preset.is_enabled();
+        let resolution = meta.resolve(preset_name);
+        let resolved_mods = meta.resolved_mods(preset_name, presets);
+
+        let (excluded_mods, disable_presets) = if enable {
+            let (mods, presets_off) = meta.excluded_by(&resolved_mods, &resolution.presets);
+            let mut enabled_off: Vec<String> = presets_off
+                .into_iter()
+                .filter(|name| presets.iter().any(|(n, p)| n == name && p.is_enabled()))
+                .collect();
+            // Presets that would turn an excluded mod back on the next time rules are applied
+            for name in enabled_presets_containing(&mods, presets, meta) {
+                if !enabled_off.contains(&name) && !resolution.presets.contains(&name) {
+                    enabled_off.push(name);
+                }
+            }
+            (mods, enabled_off)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        let before: HashMap<String, bool> = staged_mods
+            .iter()
+            .map(|m| (m.mod_name.clone(), m.active))
+            .collect();
+        let mut after = before.clone();
+        let turned_off = presets
+            .iter()
+            .filter(|(name, _)| (name == preset_name && !enable) || disable_presets.contains(name));
+        for (name, _) in turned_off {
+            set_existing(&mut after, &meta.resolved_mods(name, presets), false);
+        }
+        set_existing(&mut after, &excluded_mods, false);
+        apply_preset_rules(&mut after, presets, meta, |name, p| {
+            if name == preset_name {
+                enable
+            } else {
+                p.is_enabled() && !disable_presets.iter().any(|n| n == name)
+            }
+        });
+
+        let mut activate = Vec::new();
+        let mut deactivate = Vec::new();
+        for (mod_name, active) in &after {
+            if before[mod_name] != *active {
+                if *active {
+                    activate.push(mod_name.clone());
+                } else {
+                    deactivate.push(mod_name.clone());
+                }
+            }
+        }
+        activate.sort();
+        deactivate.sort();
+
+        let shared = resolved_mods
+            .iter()
+            .filter_map(|mod_name| {
+                let others: Vec<String> = required_by
+                    .get(mod_name)?
+                    .iter()
+                    .filter(|name| *name != preset_name)
+                    .cloned()
+                    .collect();
+                (!others.is_empty()).then(|| (mod_name.clone(), others))
+            })
+            .collect();
+        let missing = resolved_mods
+            .iter()
+            .filter(|m| !before.contains_key(m.as_str()))
+            .cloned()
+            .collect();
+
+        Some(Self {
+            preset: preset_name.to_owned(),
+            enable,
+            activate,
+            deactivate,
+            disable_presets,
+            shared,
+            missing,
+            cycle: resolution.cycle.filter(|_| enable),
+        })
+    }
+}
+
+/// Apply every preset for which `enabled` returns true to `active`, in priority order: each
+/// turns its mods on and its excluded mods off, so a later preset overrides an earlier one.
+/// A preset's includes are applied just before it, so its own rules win over theirs.
+/// Mods missing from `active` (not installed) are skipped. Where the presets turn on several
+/// mods of one exclusion group, only the one from the highest priority preset stays on; the
+/// others are returned.
+pub fn apply_preset_rules(
+    active: &mut HashMap<String, bool>,
+    presets: &[(String, Preset)],
+    meta: &PresetMeta,
+    enabled: impl Fn(&str, &Preset) -> bool,
+) -> Vec<String> {
+    for (name, _) in presets.iter().filter(|(name, p)| enabled(name, p)) {
+        for part in meta.resolve(name).presets {
+            if let Some((_, preset)) = presets.iter().find(|(n, _)| *n == part) {
+                set_existing(active, preset.get_mods(), true);
+            }
+            set_existing(active, meta.excludes(&part), false);
+        }
+    }
+    let turned_on: Vec<String> = presets
+        .iter()
+        .rev()
+        .filter(|(name, p)| enabled(name, p))
+        .flat_map(|(name, _)| meta.resolved_mods(name, presets))
+        .filter(|m| active.get(m) == Some(&true))
+        .collect();
+    let (_, dropped) = meta.keep_one_per_group(&turned_on);
+    set_existing(active, &dropped, false);
+    dropped
+}
+
+/// Enabled presets whose mods, including those of presets they include, contain any of
+/// `mod_names`.
+pub fn enabled_presets_containing(
+    mod_names: &[String],
+    presets: &[(String, Preset)],
+    meta: &PresetMeta,
+) -> Vec<String> {
+    presets
+        .iter()
+        .filter(|(name, p)| {
+            p.is_enabled()
+                && meta
+                    .resolved_mods(name, presets)
+                    .iter()
+                    .any(|m| mod_names.contains(m))
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Add the mods `preset` doesn't already have. `Preset::add_mods` doesn't check, so adding an
+/// overlapping selection would list a mod twice.
+pub fn add_new_mods(preset: &mut Preset, mod_names: &[String]) {
+    let new_mods: Vec<String> = mod_names
+        .iter()
+        .filter(|m| !preset.get_mods().contains(m))
+        .fold(Vec::new(), |mut new_mods, m| {
+            if !new_mods.contains(m) {
+                new_mods.push(m.clone());
+            }
+            new_mods
+        });
+    if !new_mods.is_empty() {
+        preset.add_mods(&new_mods);
+    }
+}
+
+fn set_existing(active: &mut HashMap<String, bool>, mod_names: &[String], value: bool) {
+    for mod_name in mod_names {
+        if let Some(state) = active.get_mut(mod_name) {
+            *state = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preset_meta::{ExclusionGroup, GroupMember};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn preset(name: &str, mods: &[&str], enabled: bool) -> (String, Preset) {
+        let mut preset = Preset::new(name.to_owned(), names(mods));
+        if enabled {
+            preset.enable();
+        }
+        (name.to_owned(), preset)
+    }
+
+    /// Installed mods, with the given ones active.
+    fn staged(installed: &[&str], active: &[&str]) -> Vec<StagedMod> {
+        installed
+            .iter()
+            .map(|name| StagedMod {
+                mod_name: name.to_string(),
+                selected: false,
+                active: active.contains(name),
+                createtime: None,
+                filename: None,
+                fullpath: None,
+                mod_type: None,
+                size: None,
+                title: None,
+                author: None,
+                version: None,
+                hash: None,
+            })
+            .collect()
+    }
+
+    fn plan(name: &str, presets: &[(String, Preset)], meta: &PresetMeta, staged_mods: &[StagedMod]) -> PresetPlan {
+        let mut required_by: HashMap<String, Vec<String>> = HashMap::new();
+        for (preset_name, _) in presets.iter().filter(|(_, p)| p.is_enabled()) {
+            for mod_name in meta.resolved_mods(preset_name, presets) {
+                required_by.entry(mod_name).or_default().push(preset_name.clone());
+            }
+        }
+        PresetPlan::new(name, presets, meta, &required_by, staged_mods).unwrap()
+    }
+
+    #[test]
+    fn enabling_turns_on_the_presets_mods() {
+        let presets = vec![preset("racing", &["a", "b"], false)];
+        let plan = plan("racing", &presets, &PresetMeta::default(), &staged(&["a", "b", "c"], &["b"]));
+        assert!(plan.enable);
+        assert_eq!(plan.activate, names(&["a"]));
+        assert!(plan.deactivate.is_empty());
+    }
+
+    #[test]
+    fn higher_priority_preset_wins_when_enabling_below_it() {
+        // "strict" comes later, so its exclude of "a" wins over "racing" listing it
+        let presets = vec![preset("racing", &["a", "b"], false), preset("strict", &["c"], true)];
+        let mut meta = PresetMeta::default();
+        meta.settings_mut("strict").excludes.push("a".to_owned());
+        let plan = plan("racing", &presets, &meta, &staged(&["a", "b", "c"], &["c"]));
+        assert_eq!(plan.activate, names(&["b"]));
+        assert!(plan.deactivate.is_empty());
+    }
+
+    #[test]
+    fn enabling_above_a_preset_overrides_it() {
+        // "racing" comes later, so it turns "a" back on despite "strict" excluding it
+        let presets = vec![preset("strict", &["c"], true), preset("racing", &["a"], false)];
+        let mut meta = PresetMeta::default();
+        meta.settings_mut("strict").excludes.push("a".to_owned());
+        let plan = plan("racing", &presets, &meta, &staged(&["a", "c"], &["c"]));
+        assert_eq!(plan.activate, names(&["a"]));
+    }
+
+    #[test]
+    fn enabling_applies_own_excludes() {
+        let presets = vec![preset("racing", &["a"], false)];
+        let mut meta = PresetMeta::default();
+        meta.settings_mut("racing").excludes.push("b".to_owned());
+        let plan = plan("racing", &presets, &meta, &staged(&["a", "b"], &["b"]));
+        assert_eq!(plan.activate, names(&["a"]));
+        assert_eq!(plan.deactivate, names(&["b"]));
+    }
+
+    #[test]
+    fn disabling_keeps_mods_other_enabled_presets_need() {
+        let presets = vec![preset("racing", &["a", "b"], true), preset("drift", &["b", "c"], true)];
+        let plan = plan("drift", &presets, &PresetMeta::default(), &staged(&["a", "b", "c"], &["a", "b", "c"]));
+        assert!(!plan.enable);
+        assert_eq!(plan.deactivate, names(&["c"]));
+        assert!(plan.activate.is_empty());
+        assert_eq!(plan.shared, vec![("b".to_owned(), names(&["racing"]))]);
+    }
+
+    #[test]
+    fn exclusion_group_with_a_preset_disables_it() {
+        let presets = vec![preset("racing", &["a", "b"], true), preset("drift", &["c"], false)];
+        let mut meta = PresetMeta::default();
+        meta.exclusion_groups.push(ExclusionGroup {
+            name: "style".to_owned(),
+            members: vec![GroupMember::Preset("racing".to_owned()), GroupMember::Preset("drift".to_owned())],
+        });
+        let plan = plan("drift", &presets, &meta, &staged(&["a", "b", "c"], &["a", "b"]));
+        assert_eq!(plan.disable_presets, names(&["racing"]));
+        assert_eq!(plan.activate, names(&["c"]));
+        assert_eq!(plan.deactivate, names(&["a", "b"]));
+    }
+
+    #[test]
+    fn exclusion_group_mod_disables_presets_containing_it() {
+        let presets = vec![preset("racing", &["a", "b"], true), preset("drift", &["c"], false)];
+        let mut meta = PresetMeta::default();
+        meta.exclusion_groups.push(ExclusionGroup {
+            name: "tires".to_owned(),
+            members: vec![GroupMember::Mod("a".to_owned()), GroupMember::Mod("c".to_owned())],
+        });
+        let plan = plan("drift", &presets, &meta, &staged(&["a", "b", "c"], &["a", "b"]));
+        // "racing" would turn "a" back on the next time rules are applied
+        assert_eq!(plan.disable_presets, names(&["racing"]));
+        assert_eq!(plan.deactivate, names(&["a", "b"]));
+    }
+
+    #[test]
+    fn exclusion_group_members_in_presets_keep_only_one_on() {
+        let presets = vec![preset("old", &["a"], true), preset("new", &["b", "c"], false)];
+        let mut meta = PresetMeta::default();
+        meta.exclusion_groups.push(ExclusionGroup {
+            name: "tires".to_owned(),
+            members: vec![GroupMember::Mod("a".to_owned()), GroupMember::Mod("b".to_owned())],
+        });
+        let mut active: HashMap<String, bool> =
+            ["a", "b", "c"].into_iter().map(|m| (m.to_owned(), false)).collect();
+        let dropped = apply_preset_rules(&mut active, &presets, &meta, |_, _| true);
+        // "new" has the higher priority, so its member of the group wins
+        assert_eq!(dropped, names(&["a"]));
+        assert!(!active["a"]);
+        assert!(active["b"] && active["c"]);
+    }
+
+    #[test]
+    fn reports_missing_mods() {
+        let presets = vec![preset("racing", &["a", "gone"], false)];
+        let plan = plan("racing", &presets, &PresetMeta::default(), &staged(&["a"], &[]));
+        assert_eq!(plan.missing, names(&["gone"]));
+        assert_eq!(plan.activate, names(&["a"]));
+    }
+
+    #[test]
+    fn reports_include_cycles_only_when_enabling() {
+        let presets = vec![preset("a", &["x"], false), preset("b", &["y"], true)];
+        let mut meta = PresetMeta::default();
+        meta.settings_mut("a").includes.push("b".to_owned());
+        meta.settings_mut("b").includes.push("a".to_owned());
+        let enabling = plan("a", &presets, &meta, &staged(&["x", "y"], &["y"]));
+        assert_eq!(enabling.cycle, Some(names(&["a", "b", "a"])));
+        let disabling = plan("b", &presets, &meta, &staged(&["x", "y"], &["y"]));
+        assert_eq!(disabling.cycle, None);
+    }
+
+    #[test]
+    fn includes_apply_before_the_including_preset() {
+        let presets = vec![preset("base", &["a"], false), preset("racing", &["b"], false)];
+        let mut meta = PresetMeta::default();
+        meta.settings_mut("racing").includes.push("base".to_owned());
+        meta.settings_mut("racing").excludes.push("a".to_owned());
+        let mut active: HashMap<String, bool> = [("a", false), ("b", false)]
+            .into_iter()
+            .map(|(m, a)| (m.to_owned(), a))
+            .collect();
+        apply_preset_rules(&mut active, &presets, &meta, |name, _| name == "racing");
+        // The includer's exclude beats its include
+        assert!(!active["a"]);
+        assert!(active["b"]);
+    }
+
+    #[test]
+    fn finds_enabled_presets_with_mods_through_includes() {
+        let presets = vec![
+            preset("base", &["a"], false),
+            preset("racing", &["b"], true),
+            preset("drift", &["c"], true),
+            preset("off", &["a"], false),
+        ];
+        let mut meta = PresetMeta::default();
+        meta.settings_mut("drift").includes.push("base".to_owned());
+        assert_eq!(
+            enabled_presets_containing(&names(&["a"]), &presets, &meta),
+            names(&["drift"])
+        );
+        assert_eq!(
+            enabled_presets_containing(&names(&["b", "c"]), &presets, &meta),
+            names(&["racing", "drift"])
+        );
+    }
+
+    #[test]
+    fn adding_overlapping_mods_keeps_them_unique() {
+        let mut preset = Preset::new("racing".to_owned(), names(&["a", "b"]));
+        add_new_mods(&mut preset, &names(&["b", "c", "c", "a", "d"]));
+        assert_eq!(preset.get_mods(), &names(&["a", "b", "c", "d"]));
+    }
+
+    #[test]
+    fn adding_known_mods_changes_nothing() {
+        let mut preset = Preset::new("racing".to_owned(), names(&["a", "b"]));
+        add_new_mods(&mut preset, &names(&["a"]));
+        assert_eq!(preset.get_mods(), &names(&["a", "b"]));
+    }
+}
@@ -0,0 +1,127 @@
+//! Named sets of game and BeamMM folders, for running against more than one BeamNG.drive
+//! userfolder (e.g. stable and beta side by side). Stored in the GUI config.
+
+use crate::preset_meta::PresetMeta;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// BeamNG.drive userfolder, holding one folder per game version.
+    pub beamng_dir: PathBuf,
+    /// Mods folder to use instead of the one of the game's current version.
+    #[serde(default)]
+    pub mods_dir: Option<PathBuf>,
+    /// Presets folder to use instead of the shared one in the BeamMM directory.
+    #[serde(default)]
+    pub presets_dir: Option<PathBuf>,
+}
+
+/// Text fields of the "new profile" form. Optional folders are left empty.
+#[derive(Debug, Default)]
+pub struct ProfileDraft {
+    pub name: String,
+    pub beamng_dir: String,
+    pub mods_dir: String,
+    pub presets_dir: String,
+}
+
+impl ProfileDraft {
+    /// Turn the form into a profile, or say what's wrong with it.
+    pub fn build(&self, existing: &[Profile]) -> Result<Profile, String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Give the profile a name".to_owned());
+        }
+        if existing.iter().any(|p| p.name == name) {
+            return Err(format!("There already is a profile named {}", name));
+        }
+        // Each profile needs a data folder of its own, see `data_dir`
+        if let Some(other) = existing.iter().find(|p| same_folder(&p.name, name)) {
+            return Err(format!("{} is too similar to the profile {}", name, other.name));
+        }
+        let beamng_dir = PathBuf::from(self.beamng_dir.trim());
+        if !beamng_dir.is_dir() {
+            return Err(format!("{} is not a folder", beamng_dir.display()));
+        }
+        let optional = |text: &str| Some(PathBuf::from(text.trim())).filter(|p| !p.as_os_str().is_empty());
+        let mods_dir = optional(&self.mods_dir);
+        if let Some(dir) = mods_dir.as_ref().filter(|d| !d.is_dir()) {
+            return Err(format!("{} is not a folder", dir.display()));
+        }
+        Ok(Profile {
+            name: name.to_owned(),
+            beamng_dir,
+            mods_dir,
+            // Created on first use, like the default presets folder
+            presets_dir: optional(&self.presets_dir),
+        })
+    }
+}
+
+/// Folder for a profile's own BeamMM data, so the preset metadata of one profile (order,
+/// rules, verified versions) can't leak into another's presets of the same name. Created if
+/// missing. A profile sharing the default presets folder starts from the default metadata.
+pub fn data_dir(beammm_dir: &Path, profile_name: &str, shares_presets: bool) -> std::io::Result<PathBuf> {
+    let dir = beammm_dir.join("profiles").join(folder_name(profile_name));
+    std::fs::create_dir_all(&dir)?;
+    let meta = PresetMeta::path(&dir);
+    let default_meta = PresetMeta::path(beammm_dir);
+    if shares_presets && !meta.exists() && default_meta.exists() {
+        std::fs::copy(default_meta, meta)?;
+    }
+    Ok(dir)
+}
+
+fn folder_name(profile_name: &str) -> String {
+    profile_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == ' ' { c } else { '_' })
+        .collect()
+}
+
+/// Whether two profile names would share a data folder, also on case-insensitive file
+/// systems.
+fn same_folder(a: &str, b: &str) -> bool {
+    folder_name(a).to_lowercase() == folder_name(b).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(name: &str) -> ProfileDraft {
+        ProfileDraft {
+            name: name.to_owned(),
+            beamng_dir: std::env::temp_dir().display().to_string(),
+            ..ProfileDraft::default()
+        }
+    }
+
+    #[test]
+    fn builds_a_profile() {
+        let profile = draft(" Beta ").build(&[]).unwrap();
+        assert_eq!(profile.name, "Beta");
+        assert_eq!(profile.mods_dir, None);
+        assert_eq!(profile.presets_dir, None);
+    }
+
+    #[test]
+    fn rejects_names_sharing_a_data_folder() {
+        let existing = [draft("Beta_1").build(&[]).unwrap()];
+        assert!(draft("Beta_1").build(&existing).is_err());
+        assert!(draft("Beta/1").build(&existing).is_err());
+        assert!(draft("beta:1").build(&existing).is_err());
+        assert!(draft("Beta 1").build(&existing).is_ok());
+        assert!(draft("Beta-1").build(&existing).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_folders() {
+        assert!(draft("").build(&[]).is_err());
+        let mut missing = draft("Stable");
+        missing.beamng_dir = "/does/not/exist".to_owned();
+        assert!(missing.build(&[]).is_err());
+    }
+}
@@ -0,0 +1,733 @@
+//! The search box query language.
+//!
+//! A query is a list of terms combined with `AND` (implicit between terms), `OR`, `NOT`/`-`
+//! and parentheses. Terms are either bare words, which fuzzy match the mod name, filename,
+//! fullpath, title and author, or `field:value` qualifiers:
+//!
+//! ```text
+//! type:vehicle active:yes name:"drift*" -path:repo (preset:Racing OR preset:Rally) added:>2024-01-01
+//! ```
+//!
+//! Qualified text values and bare "quoted phrases" match as case-insensitive substrings unless
+//! they contain `*` or `?`, in which case the whole value must match the wildcard pattern.
+
+use crate::filter::parse_date;
+use crate::fuzzy::fuzzy_match;
+use crate::StagedMod;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Fields accepted before a `:`, shown in the search box help.
+pub const FIELDS: &[(&str, &str)] = &[
+    ("name:", "mod name"),
+    ("file:", "filename"),
+    ("path:", "fullpath"),
+    ("type:", "mod type"),
+    ("title:", "title from mod metadata"),
+    ("author:", "author from mod metadata"),
+    ("version:", "version from mod metadata"),
+    ("active:", "yes/no"),
+    ("selected:", "yes/no"),
+    ("preset:", "member of preset"),
+    ("added:", "date, e.g. >2024-01-01 or 2024-03-01"),
+];
+
+/// A syntax error and the byte range of the query it applies to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+/// A parsed query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    /// A bare word, lowercased.
+    Fuzzy(String),
+    Name(Pattern),
+    Filename(Pattern),
+    Fullpath(Pattern),
+    ModType(Pattern),
+    Title(Pattern),
+    Author(Pattern),
+    Version(Pattern),
+    Active(bool),
+    Selected(bool),
+    Preset(Pattern),
+    Added(Comparison, i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Before,
+    AtOrBefore,
+    After,
+    AtOrAfter,
+    /// Same day.
+    On,
+}
+
+/// A lowercase text pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Contains(String),
+    Wildcard(String),
+}
+
+impl Pattern {
+    fn new(value: &str) -> Self {
+        let value = value.to_lowercase();
+        if value.contains(['*', '?']) {
+            Pattern::Wildcard(value)
+        } else {
+            Pattern::Contains(value)
+        }
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        match self {
+            Pattern::Contains(value) => text.contains(value.as_str()),
+            Pattern::Wildcard(pattern) => wildcard_match(pattern, &text),
+        }
+    }
+}
+
+/// Anything a term needs to know that isn't stored on the mod itself.
+pub struct QueryContext<'a> {
+    /// Mod name -> names of the presets containing it, see `App::preset_index`.
+    pub preset_index: &'a HashMap<String, Vec<String>>,
+}
+
+impl Expr {
+    pub fn matches(&self, staged_mod: &StagedMod, ctx: &QueryContext) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(staged_mod, ctx) && b.matches(staged_mod, ctx),
+            Expr::Or(a, b) => a.matches(staged_mod, ctx) || b.matches(staged_mod, ctx),
+            Expr::Not(e) => !e.matches(staged_mod, ctx),
+            Expr::Term(term) => term.matches(staged_mod, ctx),
+        }
+    }
+
+    /// How well a matching mod fits the fuzzy words in the query; higher is better.
+    pub fn relevance(&self, staged_mod: &StagedMod) -> i32 {
+        match self {
+            Expr::And(a, b) => a.relevance(staged_mod) + b.relevance(staged_mod),
+            Expr::Or(a, b) => a.relevance(staged_mod).max(b.relevance(staged_mod)),
+            // Negated words don't make a mod more relevant
+            Expr::Not(_) => 0,
+            Expr::Term(Term::Fuzzy(word)) => fuzzy_score(word, staged_mod).unwrap_or(0),
+            Expr::Term(_) => 0,
+        }
+    }
+
+    /// The fuzzy words that count towards a match (i.e. not under a `NOT`).
+    pub fn fuzzy_words(&self) -> Vec<&str> {
+        let mut words = Vec::new();
+        self.collect_fuzzy_words(&mut words);
+        words
+    }
+
+    fn collect_fuzzy_words<'a>(&'a self, words: &mut Vec<&'a str>) {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                a.collect_fuzzy_words(words);
+                b.collect_fuzzy_words(words);
+            }
+            Expr::Not(_) => {}
+            Expr::Term(Term::Fuzzy(word)) => words.push(word),
+            Expr::Term(_) => {}
+        }
+    }
+}
+
+/// Best weighted fuzzy score of `word` over a mod's text fields. Name and title hits
+/// outrank the same hit buried in a path.
+fn fuzzy_score(word: &str, staged_mod: &StagedMod) -> Option<i32> {
+    let fields = [
+        (Some(staged_mod.mod_name.as_str()), 4),
+        (staged_mod.title.as_deref(), 4),
+        (staged_mod.author.as_deref(), 2),
+    ];
+    let paths = [
+        (staged_mod.filename.as_deref(), 3),
+        (staged_mod.fullpath.as_deref(), 1),
+    ];
+    let text_scores = fields
+        .into_iter()
+        .filter_map(|(value, weight)| Some(fuzzy_match(word, value?)?.score * weight));
+    // Every mod lives under `/mods/…zip`, so scattered letters in a path say nothing
+    let path_scores = paths.into_iter().filter_map(|(value, weight)| {
+        let found = fuzzy_match(word, path_text(value?))?;
+        let contiguous = found.indices.windows(2).all(|w| w[1] == w[0] + 1);
+        contiguous.then_some(found.score * weight)
+    });
+    text_scores.chain(path_scores).max()
+}
+
+/// A db.json filename or fullpath without the parts all mods share: the `/mods/` and
+/// `repo/` prefixes and the `.zip` extension.
+fn path_text(path: &str) -> &str {
+    let path = path.strip_prefix("/mods/").unwrap_or(path);
+    let path = path.strip_prefix("repo/").unwrap_or(path);
+    match path.len().checked_sub(4) {
+        Some(end) if path.is_char_boundary(end) && path[end..].eq_ignore_ascii_case(".zip") => &path[..end],
+        _ => path,
+    }
+}
+
+impl Term {
+    fn matches(&self, staged_mod: &StagedMod, ctx: &QueryContext) -> bool {
+        let optional = |value: &Option<String>, pattern: &Pattern| {
+            pattern.matches(value.as_deref().unwrap_or(""))
+        };
+        match self {
+            Term::Fuzzy(word) => fuzzy_score(word, staged_mod).is_some(),
+            Term::Name(pattern) => pattern.matches(&staged_mod.mod_name),
+            Term::Filename(pattern) => optional(&staged_mod.filename, pattern),
+            Term::Fullpath(pattern) => optional(&staged_mod.fullpath, pattern),
+            Term::ModType(pattern) => optional(&staged_mod.mod_type, pattern),
+            Term::Title(pattern) => optional(&staged_mod.title, pattern),
+            Term::Author(pattern) => optional(&staged_mod.author, pattern),
+            Term::Version(pattern) => optional(&staged_mod.version, pattern),
+            Term::Active(active) => staged_mod.active == *active,
+            Term::Selected(selected) => staged_mod.selected == *selected,
+            Term::Preset(pattern) => ctx
+                .preset_index
+                .get(&staged_mod.mod_name)
+                .is_some_and(|names| names.iter().any(|name| pattern.matches(name))),
+            Term::Added(comparison, day) => {
+                let Some(time) = staged_mod.createtime else {
+                    return false;
+                };
+                let next_day = day + 86_400;
+                match comparison {
+                    Comparison::Before => time < *day,
+                    Comparison::AtOrBefore => time < next_day,
+                    Comparison::After => time >= next_day,
+                    Comparison::AtOrAfter => time >= *day,
+                    Comparison::On => (*day..next_day).contains(&time),
+                }
+            }
+        }
+    }
+}
+
+/// The search box text together with its parsed form.
+///
+/// While the text has a syntax error the last valid query stays in effect, so the table
+/// doesn't blank out mid-typing.
+#[derive(Debug, Default)]
+pub struct Search {
+    text: String,
+    pub expr: Option<Expr>,
+    pub error: Option<ParseError>,
+}
+
+impl Search {
+    /// Re-parse if `text` differs from what was parsed last. Returns true if it was re-parsed.
+    pub fn update(&mut self, text: &str) -> bool {
+        if self.text == text {
+            return false;
+        }
+        self.text = text.to_owned();
+        match parse(text) {
+            Ok(expr) => {
+                self.expr = expr;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+        true
+    }
+}
+
+/// Parse a query. An empty (or all-whitespace) query yields `None`, matching everything.
+pub fn parse(text: &str) -> Result<Option<Expr>, ParseError> {
+    let tokens = lex(text)?;
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: text.len(),
+    };
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(Some(expr)),
+        Some(token) => Err(ParseError {
+            message: "Unexpected ')'".to_owned(),
+            span: token.span.clone(),
+        }),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    Minus,
+    Colon,
+    Word(String),
+    Quoted(String),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    span: Range<usize>,
+}
+
+fn lex(text: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        let kind = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                TokenKind::LParen
+            }
+            ')' => {
+                chars.next();
+                TokenKind::RParen
+            }
+            ':' => {
+                chars.next();
+                TokenKind::Colon
+            }
+            // Words swallow inner dashes (e.g. dates), so a `-` here always starts a negation
+            '-' => {
+                chars.next();
+                TokenKind::Minus
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, c)) => value.push(c),
+                        None => {
+                            return Err(ParseError {
+                                message: "Missing closing quote".to_owned(),
+                                span: start..text.len(),
+                            })
+                        }
+                    }
+                }
+                TokenKind::Quoted(value)
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | ':' | '"') {
+                        break;
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+                TokenKind::Word(value)
+            }
+        };
+        let end = chars.peek().map_or(text.len(), |&(i, _)| i);
+        tokens.push(Token {
+            kind,
+            span: start..end,
+        });
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Length of the query, used for errors at the very end.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token { kind: TokenKind::Word(w), .. }) if w == keyword)
+    }
+
+    fn error_at_end(&self, message: &str) -> ParseError {
+        ParseError {
+            message: message.to_owned(),
+            span: self.end..self.end,
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_and()?;
+        while self.peek_keyword("OR") {
+            self.next();
+            let rhs = self.parse_and()?;
+            expr = Expr::Or(Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            if self.peek_keyword("AND") {
+                self.next();
+            } else if self.peek().is_none()
+                || self.peek_keyword("OR")
+                || matches!(self.peek(), Some(Token { kind: TokenKind::RParen, .. }))
+            {
+                break;
+            }
+            // Juxtaposed terms are an implicit AND
+            let rhs = self.parse_unary()?;
+            expr = Expr::And(Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        if self.peek_keyword("NOT")
+            || matches!(self.peek(), Some(Token { kind: TokenKind::Minus, .. }))
+        {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ParseError> {
+        let Some(token) = self.next() else {
+            return Err(self.error_at_end("Expected a search term"));
+        };
+        match token.kind {
+            TokenKind::LParen => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(expr),
+                    _ => Err(ParseError {
+                        message: "Missing closing ')'".to_owned(),
+                        span: token.span,
+                    }),
+                }
+            }
+            TokenKind::Word(word) => {
+                if matches!(self.peek(), Some(Token { kind: TokenKind::Colon, .. })) {
+                    self.next();
+                    self.parse_qualified(&word, token.span)
+                } else if matches!(word.as_str(), "AND" | "OR") {
+                    Err(ParseError {
+                        message: format!("Expected a search term before '{}'", word),
+                        span: token.span,
+                    })
+                } else {
+                    Ok(Expr::Term(Term::Fuzzy(word.to_lowercase())))
+                }
+            }
+            TokenKind::Quoted(value) => Ok(Expr::Term(Term::Name(Pattern::new(&value)))),
+            TokenKind::RParen => Err(ParseError {
+                message: "Unexpected ')'".to_owned(),
+                span: token.span,
+            }),
+            TokenKind::Colon => Err(ParseError {
+                message: "Expected a field name before ':'".to_owned(),
+                span: token.span,
+            }),
+            TokenKind::Minus => unreachable!("handled by parse_unary"),
+        }
+    }
+
+    /// Parse the value after `field:` into a term.
+    fn parse_qualified(&mut self, field: &str, field_span: Range<usize>) -> Result<Expr, ParseError> {
+        let (value, value_span) = match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(value) | TokenKind::Quoted(value),
+                span,
+            }) => (value, span),
+            _ => {
+                return Err(ParseError {
+                    message: format!("Expected a value after '{}:'", field),
+                    span: field_span,
+                })
+            }
+        };
+        let value_error = |message: String| ParseError {
+            message,
+            span: value_span.clone(),
+        };
+
+        let term = match field.to_lowercase().as_str() {
+            "name" => Term::Name(Pattern::new(&value)),
+            "file" | "filename" => Term::Filename(Pattern::new(&value)),
+            "path" | "fullpath" => Term::Fullpath(Pattern::new(&value)),
+            "type" => Term::ModType(Pattern::new(&value)),
+            "title" => Term::Title(Pattern::new(&value)),
+            "author" => Term::Author(Pattern::new(&value)),
+            "version" => Term::Version(Pattern::new(&value)),
+            "preset" => Term::Preset(Pattern::new(&value)),
+            "active" => Term::Active(
+                parse_bool(&value)
+                    .ok_or_else(|| value_error(format!("Expected yes or no, got '{}'", value)))?,
+            ),
+            "selected" => Term::Selected(
+                parse_bool(&value)
+                    .ok_or_else(|| value_error(format!("Expected yes or no, got '{}'", value)))?,
+            ),
+            "added" => {
+                let (comparison, date) = if let Some(rest) = value.strip_prefix(">=") {
+                    (Comparison::AtOrAfter, rest)
+                } else if let Some(rest) = value.strip_prefix("<=") {
+                    (Comparison::AtOrBefore, rest)
+                } else if let Some(rest) = value.strip_prefix('>') {
+                    (Comparison::After, rest)
+                } else if let Some(rest) = value.strip_prefix('<') {
+                    (Comparison::Before, rest)
+                } else {
+                    (Comparison::On, value.strip_prefix('=').unwrap_or(&value))
+                };
+                let day = parse_date(date).ok_or_else(|| {
+                    value_error(format!("Expected a date like 2024-01-31, got '{}'", date))
+                })?;
+                Term::Added(comparison, day)
+            }
+            _ => {
+                return Err(ParseError {
+                    message: format!("Unknown field '{}'", field),
+                    span: field_span,
+                })
+            }
+        };
+        Ok(Expr::Term(term))
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" | "on" => Some(true),
+        "no" | "n" | "false" | "0" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?` is one character.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it is currently standing in for
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(t: Term) -> Expr {
+        Expr::Term(t)
+    }
+
+    fn fuzzy(word: &str) -> Expr {
+        term(Term::Fuzzy(word.to_owned()))
+    }
+
+    fn and(a: Expr, b: Expr) -> Expr {
+        Expr::And(Box::new(a), Box::new(b))
+    }
+
+    fn or(a: Expr, b: Expr) -> Expr {
+        Expr::Or(Box::new(a), Box::new(b))
+    }
+
+    fn not(a: Expr) -> Expr {
+        Expr::Not(Box::new(a))
+    }
+
+    fn parsed(text: &str) -> Expr {
+        parse(text).unwrap().unwrap()
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(parse("").unwrap(), None);
+        assert_eq!(parse("   ").unwrap(), None);
+    }
+
+    #[test]
+    fn qualifiers() {
+        assert_eq!(parsed("type:Vehicle"), term(Term::ModType(Pattern::Contains("vehicle".into()))));
+        assert_eq!(parsed("file:car"), term(Term::Filename(Pattern::Contains("car".into()))));
+        assert_eq!(parsed("filename:car"), term(Term::Filename(Pattern::Contains("car".into()))));
+        assert_eq!(parsed("name:drift*"), term(Term::Name(Pattern::Wildcard("drift*".into()))));
+        assert_eq!(parsed("active:yes"), term(Term::Active(true)));
+        assert_eq!(parsed("selected:off"), term(Term::Selected(false)));
+        assert_eq!(parsed("preset:Racing"), term(Term::Preset(Pattern::Contains("racing".into()))));
+        assert!(matches!(parsed("added:>2024-01-01"), Expr::Term(Term::Added(Comparison::After, _))));
+        assert!(matches!(parsed("added:<=2024-01-01"), Expr::Term(Term::Added(Comparison::AtOrBefore, _))));
+        assert!(matches!(parsed("added:2024-01-01"), Expr::Term(Term::Added(Comparison::On, _))));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(parsed("a b OR c"), or(and(fuzzy("a"), fuzzy("b")), fuzzy("c")));
+        assert_eq!(parsed("a OR b AND c"), or(fuzzy("a"), and(fuzzy("b"), fuzzy("c"))));
+        assert_eq!(parsed("a OR b OR c"), or(or(fuzzy("a"), fuzzy("b")), fuzzy("c")));
+    }
+
+    #[test]
+    fn not_binds_tightest() {
+        assert_eq!(parsed("NOT a b"), and(not(fuzzy("a")), fuzzy("b")));
+        assert_eq!(parsed("-a OR b"), or(not(fuzzy("a")), fuzzy("b")));
+        assert_eq!(
+            parsed("-path:repo"),
+            not(term(Term::Fullpath(Pattern::Contains("repo".into()))))
+        );
+        assert_eq!(parsed("NOT NOT a"), not(not(fuzzy("a"))));
+    }
+
+    #[test]
+    fn parentheses_group() {
+        assert_eq!(parsed("a (b OR c)"), and(fuzzy("a"), or(fuzzy("b"), fuzzy("c"))));
+        assert_eq!(parsed("-(a b)"), not(and(fuzzy("a"), fuzzy("b"))));
+        assert_eq!(parsed("((a))"), fuzzy("a"));
+    }
+
+    #[test]
+    fn inner_dashes_stay_in_words() {
+        assert_eq!(parsed("my-mod"), fuzzy("my-mod"));
+    }
+
+    #[test]
+    fn quoted_terms() {
+        assert_eq!(
+            parsed("\"Drift Car\""),
+            term(Term::Name(Pattern::Contains("drift car".into())))
+        );
+        assert_eq!(
+            parsed("title:\"big truck\""),
+            term(Term::Title(Pattern::Contains("big truck".into())))
+        );
+        // Keywords inside quotes are plain text
+        assert_eq!(
+            parsed("\"a OR b\""),
+            term(Term::Name(Pattern::Contains("a or b".into())))
+        );
+    }
+
+    #[test]
+    fn malformed_input_reports_position() {
+        let error = |text: &str| parse(text).unwrap_err();
+
+        assert_eq!(error("\"open").span, 0..5);
+        assert_eq!(error("(a b").span, 0..1);
+        assert_eq!(error("a)").span, 1..2);
+        assert_eq!(error("a OR").span, 4..4);
+        assert_eq!(error("OR a").span, 0..2);
+        assert_eq!(error(":x").span, 0..1);
+        assert_eq!(error("color:red").span, 0..5);
+        assert_eq!(error("type:").span, 0..4);
+        assert_eq!(error("active:maybe").span, 7..12);
+        assert_eq!(error("added:>yesterday").span, 6..16);
+        assert!(error("color:red").message.contains("Unknown field"));
+    }
+
+    fn staged_mod(mod_name: &str, fullpath: &str) -> StagedMod {
+        StagedMod {
+            mod_name: mod_name.to_owned(),
+            selected: false,
+            active: false,
+            createtime: None,
+            filename: fullpath.rsplit('/').next().map(str::to_owned),
+            fullpath: Some(fullpath.to_owned()),
+            mod_type: None,
+            size: None,
+            title: None,
+            author: None,
+            version: None,
+            hash: None,
+        }
+    }
+
+    #[test]
+    fn fuzzy_words_skip_shared_path_parts() {
+        let drift = staged_mod("drift_car", "/mods/repo/drift_car.zip");
+        assert_eq!(fuzzy_score("zip", &drift), None);
+        assert_eq!(fuzzy_score("mod", &drift), None);
+        assert_eq!(fuzzy_score("rep", &drift), None);
+        assert!(fuzzy_score("drift", &drift).is_some());
+
+        let unpacked = staged_mod("trucks", "/mods/unpacked/big_trucks");
+        assert!(fuzzy_score("unpacked", &unpacked).is_some());
+    }
+
+    #[test]
+    fn fuzzy_path_matches_must_be_contiguous() {
+        let packed = staged_mod("a", "/mods/dev/zebra_pack.zip");
+        assert!(fuzzy_score("dev", &packed).is_some());
+        // z…p is a subsequence of the path but not a run
+        assert_eq!(fuzzy_score("zp", &packed), None);
+        // The name still matches scattered letters
+        assert!(fuzzy_score("zp", &staged_mod("zebra_pack", "/mods/x.zip")).is_some());
+    }
+
+    #[test]
+    fn path_text_strips_boilerplate() {
+        assert_eq!(path_text("/mods/repo/car.zip"), "car");
+        assert_eq!(path_text("/mods/car.ZIP"), "car");
+        assert_eq!(path_text("car.zip"), "car");
+        assert_eq!(path_text("/mods/unpacked/car"), "unpacked/car");
+        assert_eq!(path_text("zip"), "zip");
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("drift*", "drift_car"));
+        assert!(wildcard_match("*car", "drift_car"));
+        assert!(wildcard_match("d?ift*", "drift"));
+        assert!(!wildcard_match("drift", "drift_car"));
+        assert!(!wildcard_match("?", ""));
+    }
+}
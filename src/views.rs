@@ -0,0 +1,69 @@
+//! Named filter + sort combinations, saved to `views.json` in the BeamMM directory.
+
+use crate::filter::ModFilter;
+use crate::SortKey;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    pub filter: ModFilter,
+    pub sort_keys: Vec<SortKey>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedViews {
+    pub views: Vec<SavedView>,
+    /// Name of the view applied at startup.
+    pub default_view: Option<String>,
+}
+
+impl SavedViews {
+    fn path(beammm_dir: &Path) -> PathBuf {
+        beammm_dir.join("views.json")
+    }
+
+    /// Load saved views, falling back to none if the file is missing or unreadable.
+    pub fn load(beammm_dir: &Path) -> Self {
+        let path = Self::path(beammm_dir);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        let parsed = serde_json::from_str(&content);
+        parsed.unwrap_or_else(|e| {
+            eprintln!("Failed to parse saved views {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, beammm_dir: &Path) {
+        let path = Self::path(beammm_dir);
+        if let Err(e) = std::fs::write(&path, serde_json::to_string_pretty(self).unwrap()) {
+            eprintln!("Failed to write saved views {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedView> {
+        self.views.iter().find(|v| v.name == name)
+    }
+
+    /// Add a view, replacing any existing view with the same name.
+    pub fn upsert(&mut self, view: SavedView) {
+        match self.views.iter_mut().find(|v| v.name == view.name) {
+            Some(existing) => *existing = view,
+            None => {
+                self.views.push(view);
+                self.views.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.views.retain(|v| v.name != name);
+        if self.default_view.as_deref() == Some(name) {
+            self.default_view = None;
+        }
+    }
+}